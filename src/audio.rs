@@ -0,0 +1,148 @@
+use crate::config::AudioConfig;
+use crate::game_constants::*;
+
+use quicksilver::Result;
+#[cfg(feature = "sounds")]
+use quicksilver::{lifecycle::Asset, sound::Sound};
+
+#[cfg(feature = "sounds")]
+const SFX_FILES: [&str; 7] = [
+    "Throw.wav",
+    "Whoosh.wav",
+    "BuildingHit.wav",
+    "GorillaHit.wav",
+    "Victory.wav",
+    "MenuMove.wav",
+    "MenuSelect.wav",
+];
+#[cfg(feature = "sounds")]
+const MUSIC_FILE: &str = "Music.wav";
+
+// the order matches SFX_FILES
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Sfx {
+    Throw,
+    Whoosh,
+    BuildingHit,
+    GorillaHit,
+    Victory,
+    MenuMove,
+    MenuSelect,
+}
+
+pub trait AudioBackend {
+    fn play_sfx(&mut self, sfx: Sfx, volume: f32) -> Result<()>;
+    fn play_music(&mut self, volume: f32) -> Result<()>;
+}
+
+// used when the game is built without sound support or no output device is available
+pub struct NullAudio;
+
+#[cfg(feature = "sounds")]
+pub struct QuicksilverAudio {
+    effects: Vec<Asset<Sound>>,
+    music: Asset<Sound>,
+}
+
+pub struct Audio {
+    backend: Box<dyn AudioBackend>,
+    music_left_ms: f32,
+}
+
+impl AudioBackend for NullAudio {
+    fn play_sfx(&mut self, _sfx: Sfx, _volume: f32) -> Result<()> {
+        Ok(())
+    }
+
+    fn play_music(&mut self, _volume: f32) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sounds")]
+impl QuicksilverAudio {
+    fn new() -> Self {
+        QuicksilverAudio {
+            effects: SFX_FILES
+                .iter()
+                .map(|file| Asset::new(Sound::load(*file)))
+                .collect(),
+            music: Asset::new(Sound::load(MUSIC_FILE)),
+        }
+    }
+}
+
+#[cfg(feature = "sounds")]
+fn play_at(sound: &mut Asset<Sound>, volume: f32) -> Result<()> {
+    sound.execute(|s| {
+        s.set_volume(volume);
+        s.play()
+    })
+}
+
+#[cfg(feature = "sounds")]
+impl AudioBackend for QuicksilverAudio {
+    fn play_sfx(&mut self, sfx: Sfx, volume: f32) -> Result<()> {
+        play_at(&mut self.effects[sfx as usize], volume)
+    }
+
+    fn play_music(&mut self, volume: f32) -> Result<()> {
+        play_at(&mut self.music, volume)
+    }
+}
+
+#[cfg(feature = "sounds")]
+fn default_backend() -> Box<dyn AudioBackend> {
+    Box::new(QuicksilverAudio::new())
+}
+
+#[cfg(not(feature = "sounds"))]
+fn default_backend() -> Box<dyn AudioBackend> {
+    Box::new(NullAudio)
+}
+
+impl Default for Audio {
+    fn default() -> Self {
+        Audio::new()
+    }
+}
+
+impl Audio {
+    pub fn new() -> Self {
+        Audio {
+            backend: default_backend(),
+            music_left_ms: 0.0,
+        }
+    }
+
+    fn check(&mut self, result: Result<()>) {
+        if let Err(e) = result {
+            eprintln!("Audio disabled: {}", e);
+            self.backend = Box::new(NullAudio);
+        }
+    }
+
+    pub fn play(&mut self, sfx: Sfx, config: &AudioConfig) {
+        if !config.muted {
+            let result = self
+                .backend
+                .play_sfx(sfx, config.master_volume * config.sfx_volume);
+            self.check(result);
+        }
+    }
+
+    // quicksilver can not stop a playing sound, so the music is restarted by hand every loop
+    // and muting only takes effect at the end of the current loop
+    pub fn update(&mut self, config: &AudioConfig) {
+        self.music_left_ms -= DELTAT_MS;
+        if self.music_left_ms <= 0.0 {
+            self.music_left_ms = MUSIC_LENGTH_MS;
+            if !config.muted {
+                let result = self
+                    .backend
+                    .play_music(config.master_volume * config.music_volume);
+                self.check(result);
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+// How the banana flies when the settings ask for more than gravity and a steady push.
+//
+// With air drag the wind no longer pushes the banana directly, the air moves with the wind
+// and the banana is slowed down by the square of its speed relative to it. Spin curves the
+// flight sideways to the speed through the air, backspin lifts and topspin dips.
+use crate::game_constants::*;
+
+use quicksilver::geom::Vector;
+use serde::{Deserialize, Serialize};
+
+// picked on the settings screen and kept in the config file, zero turns a part off
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ballistics {
+    pub drag: f32,
+    pub magnus: f32,
+}
+
+impl Default for Ballistics {
+    fn default() -> Self {
+        Ballistics {
+            drag: 0.0,
+            magnus: 0.0,
+        }
+    }
+}
+
+// what a human puts on the banana, the bots throw it straight
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Spin {
+    None,
+    Back,
+    Top,
+}
+
+impl Spin {
+    pub fn name(self) -> &'static str {
+        match self {
+            Spin::None => "no spin",
+            Spin::Back => "backspin",
+            Spin::Top => "topspin",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Spin::None => Spin::Back,
+            Spin::Back => Spin::Top,
+            Spin::Top => Spin::None,
+        }
+    }
+
+    // the turn of the banana thrown along dir, clockwise on screen is positive
+    pub fn turn(self, dir: Vector) -> f32 {
+        let lift = match self {
+            Spin::None => 0.0,
+            Spin::Back => 1.0,
+            Spin::Top => -1.0,
+        };
+        if dir.x < 0.0 {
+            lift
+        } else {
+            -lift
+        }
+    }
+}
+
+// the name of one of the choices, a value from an edited config file is shown as it is
+pub fn level_name(value: f32, choices: &[f32]) -> String {
+    match choices.iter().position(|x| *x == value) {
+        Some(i) => BALLISTICS_LEVELS[i.min(BALLISTICS_LEVELS.len() - 1)].to_string(),
+        None => value.to_string(),
+    }
+}
+
+pub fn cycle(value: f32, choices: &[f32]) -> f32 {
+    let next = choices.iter().find(|x| **x > value);
+    *next.unwrap_or(&choices[0])
+}
+
+impl Ballistics {
+    pub fn is_plain(&self) -> bool {
+        self.drag == 0.0 && self.magnus == 0.0
+    }
+
+    // one update of the banana, wind and gravity are the pushes the plain model adds every update
+    pub fn step(
+        &self,
+        pos: Vector,
+        speed: Vector,
+        wind: Vector,
+        gravity: f32,
+        turn: f32,
+    ) -> (Vector, Vector) {
+        let pos = pos + speed * DELTAT_MS;
+        if self.is_plain() {
+            return (pos, speed + wind + Vector::new(0.0, gravity));
+        }
+        let mut push = Vector::new(0.0, gravity);
+        let air = if self.drag > 0.0 {
+            wind * WIND_AIR_RATIO
+        } else {
+            push += wind;
+            Vector::ZERO
+        };
+        let relative = speed - air;
+        push -= relative * relative.len() * self.drag;
+        // sideways to the speed through the air, turned by a quarter towards the spin
+        push += Vector::new(-relative.y, relative.x) * turn * self.magnus;
+        (pos, speed + push)
+    }
+}
@@ -0,0 +1,360 @@
+// runs bot-vs-bot matches without a window and reports how the bots did
+use gorillas_rs::ballistics::Ballistics;
+use gorillas_rs::cli::{number, player, value};
+use gorillas_rs::game::{Difficulty, Game, Player, RoundSummary, Side};
+use gorillas_rs::game_constants::DELTAT_MS;
+use gorillas_rs::named;
+use gorillas_rs::personality::{self, Personality};
+use gorillas_rs::profile::Profile;
+use gorillas_rs::rules::Rules;
+use gorillas_rs::strategy::StrategyId;
+use gorillas_rs::{GameConfig, SharedData};
+
+use serde::Serialize;
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Instant;
+
+const USAGE: &str = "usage: arena [options]
+
+options:
+    --games N         number of matches to play (default 100)
+    --seed N          seed of the first match, the next ones count up from it (default 0)
+    --left PLAYER     easy, normal or hard (default normal)
+    --right PLAYER    easy, normal or hard (default normal)
+                      a bot strategy is picked with a suffix, as in hard:bracket
+                      (default classic)
+    --left-bot CMD    let a program play the left side, see src/external.rs for the protocol
+                      words are quoted like in a shell, as in 'python3 \"my bot.py\"'
+    --right-bot CMD   let a program play the right side
+    --left-personality NAME   steady, hothead, joker or machine (default steady)
+    --right-personality NAME  the personality of the right bot
+    --rounds N        rounds needed to win a match (default 1)
+    --max-frames N    a round running longer than this ends the match undecided (default 36000)
+    --json PATH       write the full report as json
+    --csv PATH        write one line per round as csv
+    -h, --help        show this message";
+
+struct Options {
+    games: u64,
+    seed: u64,
+    left: Player,
+    right: Player,
+    rounds: u32,
+    left_command: Option<String>,
+    right_command: Option<String>,
+    left_personality: Personality,
+    right_personality: Personality,
+    max_frames: u32,
+    json: Option<String>,
+    csv: Option<String>,
+    help: bool,
+}
+
+#[derive(Serialize)]
+struct MatchRecord {
+    seed: u64,
+    winner: Option<Side>,
+    rounds: Vec<RoundSummary>,
+    elapsed_ms: f64,
+}
+
+#[derive(Serialize)]
+struct Summary {
+    wins_left: u32,
+    wins_right: u32,
+    undecided: u32,
+    win_rate_left: f64,
+    win_rate_right: f64,
+    shots_to_kill_left: f64,
+    shots_to_kill_right: f64,
+    round_seconds_avg: f64,
+    round_seconds_min: f64,
+    round_seconds_max: f64,
+    total_ms: f64,
+    match_ms_avg: f64,
+}
+
+#[derive(Serialize)]
+struct Report {
+    left: Player,
+    right: Player,
+    left_command: Option<String>,
+    right_command: Option<String>,
+    rounds_to_win: u32,
+    summary: Summary,
+    matches: Vec<MatchRecord>,
+}
+
+fn named(flag: &str, arg: Option<String>) -> Result<Personality, String> {
+    let name = value(flag, arg)?;
+    let presets = personality::presets();
+    match named::find(&presets, &name) {
+        Some(found) => Ok(found.clone()),
+        None => {
+            let names = presets.iter().map(|x| x.name.as_str()).collect::<Vec<_>>();
+            Err(format!(
+                "unknown personality for {}: {} (known: {})",
+                flag,
+                name,
+                names.join(", ")
+            ))
+        }
+    }
+}
+
+fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut options = Options {
+        games: 100,
+        seed: 0,
+        left: Player::Bot(StrategyId::default(), Difficulty::Normal),
+        right: Player::Bot(StrategyId::default(), Difficulty::Normal),
+        rounds: 1,
+        left_command: None,
+        right_command: None,
+        left_personality: Personality::default(),
+        right_personality: Personality::default(),
+        max_frames: 36000,
+        json: None,
+        csv: None,
+        help: false,
+    };
+    while let Some(arg) = args.next() {
+        let flag = arg.as_str();
+        match flag {
+            "--games" => options.games = number(flag, args.next())?,
+            "--seed" => options.seed = number(flag, args.next())?,
+            "--left" => options.left = player(flag, args.next())?,
+            "--right" => options.right = player(flag, args.next())?,
+            "--rounds" => options.rounds = number(flag, args.next())?,
+            "--left-bot" => options.left_command = Some(value(flag, args.next())?),
+            "--right-bot" => options.right_command = Some(value(flag, args.next())?),
+            "--left-personality" => options.left_personality = named(flag, args.next())?,
+            "--right-personality" => options.right_personality = named(flag, args.next())?,
+            "--max-frames" => options.max_frames = number(flag, args.next())?,
+            "--json" => options.json = Some(value(flag, args.next())?),
+            "--csv" => options.csv = Some(value(flag, args.next())?),
+            "-h" | "--help" => options.help = true,
+            _ => return Err(format!("unknown option: {}", arg)),
+        }
+    }
+    if options.left == Player::Human || options.right == Player::Human {
+        return Err("the arena only plays bots against each other".to_string());
+    }
+    if options.rounds == 0 || options.games == 0 {
+        return Err("--games and --rounds must be at least 1".to_string());
+    }
+    Ok(options)
+}
+
+fn play(options: &Options, seed: u64, data: &mut SharedData) -> MatchRecord {
+    let start = Instant::now();
+    let config = GameConfig {
+        left: options.left,
+        right: options.right,
+        match_length: Some(options.rounds),
+        seed: Some(seed),
+        left_command: options.left_command.clone(),
+        right_command: options.right_command.clone(),
+        headless: true,
+        left_personality: options.left_personality.clone(),
+        right_personality: options.right_personality.clone(),
+        // only bots play here
+        left_profile: Profile::default(),
+        right_profile: Profile::default(),
+        rules: Rules::default(),
+        ballistics: Ballistics::default(),
+        practice: None,
+    };
+    let mut game = Game::new(config, &mut data.parts).expect("a headless game can not fail");
+    let mut rounds = vec![];
+    let mut frames = 0;
+    while !game.is_over() && frames < options.max_frames {
+        game.step(data);
+        frames += 1;
+        data.sounds.clear();
+        for round in data.rounds.drain(..) {
+            rounds.push(round);
+            frames = 0;
+        }
+    }
+    data.particle_buffer.clear();
+    MatchRecord {
+        seed,
+        winner: game.winner(),
+        rounds,
+        elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+    }
+}
+
+fn seconds(round: &RoundSummary) -> f64 {
+    f64::from(round.frames) * f64::from(DELTAT_MS) / 1000.0
+}
+
+fn average(total: f64, count: usize) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        total / count as f64
+    }
+}
+
+fn summarize(matches: &[MatchRecord]) -> Summary {
+    let wins = |side| matches.iter().filter(|x| x.winner == Some(side)).count();
+    let rounds = matches.iter().flat_map(|x| x.rounds.iter());
+    let shots_to_kill = |side| {
+        let won = rounds
+            .clone()
+            .filter(|x| x.winner == side)
+            .map(|x| match side {
+                Side::Left => x.shots_left,
+                Side::Right => x.shots_right,
+            })
+            .collect::<Vec<_>>();
+        average(won.iter().map(|x| f64::from(*x)).sum(), won.len())
+    };
+    let lengths = rounds.clone().map(seconds).collect::<Vec<_>>();
+    let total_ms = matches.iter().map(|x| x.elapsed_ms).sum();
+    let (wins_left, wins_right) = (wins(Side::Left), wins(Side::Right));
+    Summary {
+        wins_left: wins_left as u32,
+        wins_right: wins_right as u32,
+        undecided: (matches.len() - wins_left - wins_right) as u32,
+        win_rate_left: average(wins_left as f64, matches.len()),
+        win_rate_right: average(wins_right as f64, matches.len()),
+        shots_to_kill_left: shots_to_kill(Side::Left),
+        shots_to_kill_right: shots_to_kill(Side::Right),
+        round_seconds_avg: average(lengths.iter().sum(), lengths.len()),
+        // no rounds at all reports zero, like the averages
+        round_seconds_min: lengths.iter().copied().reduce(f64::min).unwrap_or(0.0),
+        round_seconds_max: lengths.iter().copied().reduce(f64::max).unwrap_or(0.0),
+        total_ms,
+        match_ms_avg: average(total_ms, matches.len()),
+    }
+}
+
+fn describe(player: Player, command: &Option<String>) -> String {
+    match (player, command) {
+        (_, Some(command)) => format!("`{}`", command),
+        (Player::Human, None) => "human".to_string(),
+        (Player::Bot(strategy, difficulty), None) => {
+            format!("{} {} bot", difficulty.name(), strategy.name())
+        }
+    }
+}
+
+fn print_table(report: &Report, options: &Options) {
+    let s = &report.summary;
+    println!(
+        "{} (left) vs {} (right), first to {}, seeds {}..={}",
+        describe(report.left, &report.left_command),
+        describe(report.right, &report.right_command),
+        report.rounds_to_win,
+        options.seed,
+        options.seed + options.games - 1
+    );
+    println!();
+    println!(
+        "{:<8}{:>8}{:>12}{:>16}",
+        "side", "wins", "win rate", "shots to kill"
+    );
+    println!(
+        "{:<8}{:>8}{:>11.1}%{:>16.2}",
+        "left",
+        s.wins_left,
+        s.win_rate_left * 100.0,
+        s.shots_to_kill_left
+    );
+    println!(
+        "{:<8}{:>8}{:>11.1}%{:>16.2}",
+        "right",
+        s.wins_right,
+        s.win_rate_right * 100.0,
+        s.shots_to_kill_right
+    );
+    println!("{:<8}{:>8}", "none", s.undecided);
+    println!();
+    println!(
+        "round length   avg {:.1} s   min {:.1} s   max {:.1} s",
+        s.round_seconds_avg, s.round_seconds_min, s.round_seconds_max
+    );
+    println!(
+        "simulation     {:.0} ms total, {:.2} ms per match",
+        s.total_ms, s.match_ms_avg
+    );
+}
+
+fn write_json(path: &str, report: &Report) -> io::Result<()> {
+    let file = File::create(path)?;
+    serde_json::to_writer_pretty(BufWriter::new(file), report)?;
+    Ok(())
+}
+
+fn write_csv(path: &str, report: &Report) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(
+        out,
+        "match,seed,round,winner,shots_left,shots_right,seconds"
+    )?;
+    for (i, record) in report.matches.iter().enumerate() {
+        for (j, round) in record.rounds.iter().enumerate() {
+            let winner = match round.winner {
+                Side::Left => "left",
+                Side::Right => "right",
+            };
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{:.3}",
+                i,
+                record.seed,
+                j,
+                winner,
+                round.shots_left,
+                round.shots_right,
+                seconds(round)
+            )?;
+        }
+    }
+    out.flush()
+}
+
+fn main() {
+    let options = match parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let mut data = SharedData::new();
+    let matches = (0..options.games)
+        .map(|i| play(&options, options.seed + i, &mut data))
+        .collect::<Vec<_>>();
+    let report = Report {
+        left: options.left,
+        right: options.right,
+        left_command: options.left_command.clone(),
+        right_command: options.right_command.clone(),
+        rounds_to_win: options.rounds,
+        summary: summarize(&matches),
+        matches,
+    };
+
+    print_table(&report, &options);
+    if let Some(path) = &options.json {
+        if let Err(e) = write_json(path, &report) {
+            eprintln!("Failed to write {}: {}", path, e);
+        }
+    }
+    if let Some(path) = &options.csv {
+        if let Err(e) = write_csv(path, &report) {
+            eprintln!("Failed to write {}: {}", path, e);
+        }
+    }
+}
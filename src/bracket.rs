@@ -0,0 +1,312 @@
+// A bot that remembers how its shots went and brackets the target with them.
+//
+// Every round it looks for the flattest elevation whose path clears the buildings that
+// are still standing, then narrows the power down between the most that fell short and
+// the least that went long. Where a shot landed tells it how hard the wind pushed, which
+// it uses to pick the next power inside the bracket. A shot stopped by a building on the
+// way raises the elevation and starts a new bracket. On hard the bot also remembers how
+// the wind felt compared to the flag across rounds, so its first throw allows for it.
+use crate::game::Difficulty;
+use crate::game_constants::*;
+use crate::strategy::{Shot, Strategy, World};
+
+use quicksilver::geom::{Circle, Shape, Vector};
+
+#[derive(Copy, Clone)]
+struct Throw {
+    elevation: f32,
+    power: i32,
+}
+
+// the wind as the flag showed it and as it pushed the banana, both towards the target
+struct Memory {
+    shown: f32,
+    felt: f32,
+}
+
+enum Flight {
+    Target,
+    Blocked(Vector),
+    Missed,
+    Myself,
+}
+
+pub struct Bracket {
+    difficulty: Difficulty,
+    // every round of the match
+    memory: Vec<Memory>,
+    // this round, in the same order as the landings
+    throws: Vec<Throw>,
+    // how hard the wind pushed this round's shots
+    wind: Option<f32>,
+    elevation: f32,
+    // the most power that fell short and the least that went long at this elevation
+    short: Option<i32>,
+    long: Option<i32>,
+    // the last throw was meant to blast a way through, it tells nothing about the wind
+    digging: bool,
+}
+
+// +1 when the target is to the right
+fn toward(world: &World) -> f32 {
+    if world.target.center().x < world.me.center().x {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+// how far towards the target a point is
+fn ahead(world: &World, pos: Vector) -> f32 {
+    (pos.x - world.me.center().x) * toward(world)
+}
+
+fn direction(world: &World, elevation: f32) -> Vector {
+    let angle = if toward(world) > 0.0 {
+        -elevation
+    } else {
+        elevation - 180.0
+    };
+    Vector::from_angle(angle)
+}
+
+// where a banana flies with the wind pushing it towards the target, stepped like the game,
+// the downward part of the wind is as plain on the flag as gravity. The wind is how hard it
+// pushes in the open, the wind field scales it where the banana is. With drag the wind moves
+// the air the banana is slowed down in instead
+fn path<'a>(world: &'a World, throw: Throw, wind: f32) -> impl Iterator<Item = Vector> + 'a {
+    let dir = direction(world, throw.elevation);
+    let mut pos = world.me.center() + dir * 4 * SHOT_RADIUS;
+    let mut speed = dir * SHOT_SPEED_RATIO * throw.power;
+    let push = Vector::new(wind * toward(world), world.wind.y);
+    let gravity = Vector::new(0.0, world.gravity);
+    (0..BOT_PATH_STEPS).map(move |_| {
+        let blow = push * world.wind_scale(pos);
+        let mut pull = gravity;
+        if world.drag > 0.0 {
+            let relative = speed - blow * WIND_AIR_RATIO;
+            pull -= relative * relative.len() * world.drag;
+        } else {
+            pull += blow;
+        }
+        pos += speed * DELTAT_MS;
+        speed += pull;
+        pos
+    })
+}
+
+// how far a shot gets by the time it falls back down to the given height
+fn reach(world: &World, throw: Throw, wind: f32, height: f32) -> f32 {
+    let mut last = world.me.center();
+    for pos in path(world, throw, wind) {
+        let falling = pos.y > last.y;
+        last = pos;
+        if falling && pos.y >= height {
+            break;
+        }
+    }
+    ahead(world, last)
+}
+
+// follows the path through what is left of the buildings
+fn fly(world: &World, throw: Throw, wind: f32) -> Flight {
+    for pos in path(world, throw, wind) {
+        let circle = Circle::new(pos, SHOT_RADIUS);
+        if world.buildings.iter().flatten().any(|x| circle.overlaps(x)) {
+            return Flight::Blocked(pos);
+        }
+        if pos.x < 0.0 || pos.x > WINDOW_X || pos.y > WINDOW_Y {
+            return Flight::Missed;
+        }
+        if circle.overlaps(&world.me) {
+            return Flight::Myself;
+        }
+        if circle.overlaps(&world.target) {
+            return Flight::Target;
+        }
+    }
+    Flight::Missed
+}
+
+// the least power that gets the banana to the target at this elevation, if there is one
+fn solve(world: &World, elevation: f32, wind: f32) -> Option<i32> {
+    let wanted = ahead(world, world.target.center());
+    let height = world.target.center().y;
+    let short = |power| reach(world, Throw { elevation, power }, wind, height) < wanted;
+    if short(POWER_MAX) {
+        return None;
+    }
+    let (mut low, mut high) = (0, POWER_MAX);
+    while high - low > 1 {
+        let mid = (low + high) / 2;
+        if short(mid) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some(high)
+}
+
+// the wind that would have carried the throw to where it landed
+fn feel(world: &World, throw: Throw, landing: Vector) -> f32 {
+    let got = ahead(world, landing);
+    let (mut low, mut high) = (-BOT_WIND_MAX, BOT_WIND_MAX);
+    for _ in 0..BOT_WIND_STEPS {
+        let mid = (low + high) / 2.0;
+        if reach(world, throw, mid, landing.y) < got {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+impl Bracket {
+    pub fn new(difficulty: Difficulty) -> Self {
+        Bracket {
+            difficulty,
+            memory: vec![],
+            throws: vec![],
+            wind: None,
+            elevation: 0.0,
+            short: None,
+            long: None,
+            digging: false,
+        }
+    }
+
+    pub fn create(difficulty: Difficulty) -> Box<dyn Strategy> {
+        Box::new(Bracket::new(difficulty))
+    }
+
+    // before the first landing of a round, hard scales the flag by how the wind used to feel,
+    // the median keeps a shot that glanced off a wall from throwing it off
+    fn expected_wind(&self, world: &World) -> f32 {
+        if let (None, Difficulty::Hard) = (self.wind, self.difficulty) {
+            let mut ratios = self
+                .memory
+                .iter()
+                .filter(|x| x.shown.abs() > f32::EPSILON)
+                .map(|x| x.felt / x.shown)
+                .collect::<Vec<_>>();
+            if !ratios.is_empty() {
+                ratios.sort_by(|a, b| a.total_cmp(b));
+                return ratios[ratios.len() / 2] * world.wind.x * toward(world);
+            }
+        }
+        self.wind.unwrap_or(0.0)
+    }
+
+    // starts a new bracket at the flattest clear elevation above the given one
+    fn aim(&mut self, world: &World, above: f32) -> i32 {
+        self.short = None;
+        self.long = None;
+        let wind = self.expected_wind(world);
+        let wanted = ahead(world, world.target.center());
+        let mut fallback: Option<(f32, i32, f32)> = None;
+        for &elevation in BOT_ELEVATIONS.iter().filter(|&&x| x > above) {
+            let power = match solve(world, elevation, wind) {
+                Some(power) => power,
+                None => continue,
+            };
+            match fly(world, Throw { elevation, power }, wind) {
+                Flight::Target => {
+                    self.elevation = elevation;
+                    self.digging = false;
+                    return power;
+                }
+                // nothing clears, blast a way through where the path gets furthest
+                Flight::Blocked(pos) => {
+                    let got = ahead(world, pos);
+                    if got < wanted && fallback.is_none_or(|x| got > x.2) {
+                        fallback = Some((elevation, power, got));
+                    }
+                }
+                _ => (),
+            }
+        }
+        let (elevation, power, _) = fallback.unwrap_or((45.0, POWER_MAX, 0.0));
+        self.elevation = elevation;
+        self.digging = true;
+        power
+    }
+
+    // returns whether the throw was stopped on the way
+    fn learn(&mut self, world: &World, throw: Throw, landing: Vector) -> bool {
+        let got = ahead(world, landing);
+        let wanted = ahead(world, world.target.center());
+        // stopped somewhere higher than the target stands, the wind had nothing to do with it
+        if got < wanted - world.target.size.x && landing.y < world.target.pos.y {
+            return true;
+        }
+        let felt = feel(world, throw, landing);
+        self.wind = Some(felt);
+        if felt.abs() < BOT_WIND_MAX * 0.99 {
+            self.memory.push(Memory {
+                shown: world.wind.x * toward(world),
+                felt,
+            });
+        }
+        if got < wanted {
+            self.short = Some(throw.power);
+        } else {
+            self.long = Some(throw.power);
+        }
+        false
+    }
+
+    // the power the wind says hits, as long as it is inside the bracket
+    fn next_power(&self, world: &World) -> Option<i32> {
+        let low = self.short.unwrap_or(-1);
+        let high = self.long.unwrap_or(POWER_MAX + 1);
+        if high - low <= 1 {
+            return None;
+        }
+        let guess = solve(world, self.elevation, self.expected_wind(world));
+        match guess {
+            Some(power) if low < power && power < high => Some(power),
+            _ => Some((low + high) / 2),
+        }
+    }
+}
+
+impl Strategy for Bracket {
+    fn shoot(&mut self, world: &World) -> Option<Shot> {
+        // the last throw of this round landed since the previous turn
+        let blocked = match (self.throws.last(), world.landings.last()) {
+            (Some(&throw), Some(&landing)) if !self.digging => {
+                Some(self.learn(world, throw, landing))
+            }
+            _ => None,
+        };
+
+        let power = match blocked {
+            // a new round, or the buildings changed
+            None => self.aim(world, 0.0),
+            Some(true) => self.aim(world, self.elevation),
+            Some(false) => match self.next_power(world) {
+                Some(power) => power,
+                // the bracket closed without a hit, something is in the way
+                None => self.aim(world, self.elevation),
+            },
+        };
+        self.throws.push(Throw {
+            elevation: self.elevation,
+            power,
+        });
+        Some(Shot {
+            dir: direction(world, self.elevation),
+            power,
+        })
+    }
+
+    fn new_round(&mut self) {
+        self.throws.clear();
+        self.wind = None;
+        self.short = None;
+        self.long = None;
+        self.digging = false;
+    }
+}
@@ -0,0 +1,265 @@
+use crate::config::CONFIG_PATH;
+use crate::game::{Difficulty, Player};
+use crate::game_constants::{GRAVITY_MAX, RICOCHET_BOUNCE_CHOICES};
+use crate::rules::{Expiry, Flood, Rules};
+use crate::strategy::{StrategyId, STRATEGIES};
+use crate::tournament::Format;
+
+use std::str::FromStr;
+
+pub const USAGE: &str = "usage: gorillas-rs [options]
+
+options:
+    --seed N          generate the terrain and the wind from a fixed seed
+    --left PLAYER     human, or a bot as easy, normal or hard (default human)
+    --right PLAYER    human, or a bot as easy, normal or hard (default human)
+                      a bot strategy is picked with a suffix, as in hard:bracket
+                      (default classic)
+    --left-bot CMD    let a program play the left side, see src/external.rs for the protocol
+                      words are quoted like in a shell, as in 'python3 \"my bot.py\"'
+    --right-bot CMD   let a program play the right side
+    --left-personality NAME   how the left bot behaves on screen: steady, hothead, joker,
+                              machine or one from the config file (default steady)
+    --right-personality NAME  how the right bot behaves on screen
+    --left-profile NAME       the profile the left human plays under (default the first one)
+    --right-profile NAME      the profile the right human plays under (default the second one)
+    --rounds N        the first side to win N rounds wins the match (default endless,
+                      2 in a tournament)
+    --shot-clock N    humans have N seconds for each throw, 0 turns the clock off
+                      (default from the config file)
+    --forfeit         running out of time loses the turn instead of throwing
+    --objects         put moving platforms, birds, a blimp and billboards in the way
+    --ricochet        every banana bounces off the walls it glances off
+    --bounces N       how often a banana can bounce, 1 to 5 (default from the config file)
+    --wind-field      the wind is stronger higher up and weaker behind the buildings
+    --gravity G       gravity in m/s², 9.8 is the earth's, 1.6 the moon's (default from
+                      the config file)
+    --self-hit-penalty hitting yourself also costs one of your own points
+    --no-friendly-fire a banana can not hurt its own thrower
+    --sudden-death N  after N turns of a round the flood starts to rise, 0 turns it off
+                      (default from the config file)
+    --lava            the flood of sudden death is lava instead of water
+    --tournament LIST start a tournament between 4 to 16 comma separated profile names
+                      and bots, as in \"Player 1,Player 2,hard,normal:bracket\"
+    --format FORMAT   single-elimination or round-robin (default single-elimination)
+    --config PATH     settings file (default gorillas.json)
+    --window WxH      window size in pixels (default 800x600)
+    --play            skip the main menu and start the match right away
+    --practice        skip the main menu and throw at the targets of the practice range
+    -h, --help        show this message";
+
+pub struct Options {
+    pub seed: Option<u64>,
+    pub left: Player,
+    pub right: Player,
+    pub rounds: Option<u32>,
+    pub left_command: Option<String>,
+    pub right_command: Option<String>,
+    pub left_personality: Option<String>,
+    pub right_personality: Option<String>,
+    pub left_profile: Option<String>,
+    pub right_profile: Option<String>,
+    // none keeps the rules of the config file
+    pub shot_clock: Option<Option<u32>>,
+    pub expiry: Option<Expiry>,
+    pub objects: bool,
+    pub ricochet: bool,
+    pub bounces: Option<u32>,
+    pub wind_field: bool,
+    pub gravity: Option<f32>,
+    pub self_hit_penalty: bool,
+    pub friendly_fire: bool,
+    pub sudden_death: Option<Option<u32>>,
+    pub flood: Option<Flood>,
+    pub tournament: Vec<String>,
+    pub format: Format,
+    pub config_path: String,
+    pub window: (u32, u32),
+    pub play: bool,
+    pub practice: bool,
+    pub help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            seed: None,
+            left: Player::Human,
+            right: Player::Human,
+            rounds: None,
+            left_command: None,
+            right_command: None,
+            left_personality: None,
+            right_personality: None,
+            left_profile: None,
+            right_profile: None,
+            shot_clock: None,
+            expiry: None,
+            objects: false,
+            ricochet: false,
+            bounces: None,
+            wind_field: false,
+            gravity: None,
+            self_hit_penalty: false,
+            friendly_fire: true,
+            sudden_death: None,
+            flood: None,
+            tournament: vec![],
+            format: Format::SingleElimination,
+            config_path: CONFIG_PATH.to_string(),
+            window: (800, 600),
+            play: false,
+            practice: false,
+            help: false,
+        }
+    }
+}
+
+impl Options {
+    // the rules of the config file with the flags on top
+    pub fn rules(&self, rules: Rules) -> Rules {
+        Rules {
+            shot_clock: self.shot_clock.unwrap_or(rules.shot_clock),
+            expiry: self.expiry.unwrap_or(rules.expiry),
+            objects: self.objects || rules.objects,
+            ricochet: self.ricochet || rules.ricochet,
+            bounces: self.bounces.unwrap_or(rules.bounces),
+            wind_field: self.wind_field || rules.wind_field,
+            gravity: self.gravity.unwrap_or(rules.gravity),
+            self_hit_penalty: self.self_hit_penalty || rules.self_hit_penalty,
+            friendly_fire: self.friendly_fire && rules.friendly_fire,
+            sudden_death: self.sudden_death.unwrap_or(rules.sudden_death),
+            flood: self.flood.unwrap_or(rules.flood),
+        }
+    }
+}
+
+pub fn value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("{} needs a value", flag))
+}
+
+pub fn number<T: FromStr>(flag: &str, arg: Option<String>) -> Result<T, String> {
+    let arg = value(flag, arg)?;
+    arg.parse()
+        .map_err(|_| format!("invalid value for {}: {}", flag, arg))
+}
+
+// a bot is given as difficulty[:strategy]
+pub fn player(flag: &str, arg: Option<String>) -> Result<Player, String> {
+    let arg = value(flag, arg)?;
+    let mut words = arg.splitn(2, ':');
+    let difficulty = match words.next() {
+        Some("human") if words.clone().next().is_none() => return Ok(Player::Human),
+        Some("easy") => Difficulty::Easy,
+        Some("normal") | Some("bot") => Difficulty::Normal,
+        Some("hard") => Difficulty::Hard,
+        _ => return Err(format!("invalid player for {}: {}", flag, arg)),
+    };
+    let strategy = match words.next() {
+        Some(name) => StrategyId::find(name).ok_or_else(|| {
+            let names = STRATEGIES.iter().map(|x| x.name).collect::<Vec<_>>();
+            format!(
+                "unknown strategy for {}: {} (known: {})",
+                flag,
+                name,
+                names.join(", ")
+            )
+        })?,
+        None => StrategyId::default(),
+    };
+    Ok(Player::Bot(strategy, difficulty))
+}
+
+fn format(flag: &str, arg: Option<String>) -> Result<Format, String> {
+    let arg = value(flag, arg)?;
+    match arg.as_str() {
+        "single-elimination" => Ok(Format::SingleElimination),
+        "round-robin" => Ok(Format::RoundRobin),
+        _ => Err(format!("invalid format for {}: {}", flag, arg)),
+    }
+}
+
+fn window(flag: &str, arg: Option<String>) -> Result<(u32, u32), String> {
+    let arg = value(flag, arg)?;
+    let mut sizes = arg.splitn(2, 'x').map(|x| x.parse::<u32>());
+    match (sizes.next(), sizes.next()) {
+        (Some(Ok(x)), Some(Ok(y))) if x > 0 && y > 0 => Ok((x, y)),
+        _ => Err(format!("invalid window size for {}: {}", flag, arg)),
+    }
+}
+
+pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        let flag = arg.as_str();
+        match flag {
+            "--seed" => options.seed = Some(number(flag, args.next())?),
+            "--left" => options.left = player(flag, args.next())?,
+            "--right" => options.right = player(flag, args.next())?,
+            "--rounds" => {
+                let rounds = number(flag, args.next())?;
+                if rounds == 0 {
+                    return Err("--rounds must be at least 1".to_string());
+                }
+                options.rounds = Some(rounds);
+            }
+            "--left-bot" => options.left_command = Some(value(flag, args.next())?),
+            "--right-bot" => options.right_command = Some(value(flag, args.next())?),
+            "--left-personality" => options.left_personality = Some(value(flag, args.next())?),
+            "--right-personality" => options.right_personality = Some(value(flag, args.next())?),
+            "--left-profile" => options.left_profile = Some(value(flag, args.next())?),
+            "--right-profile" => options.right_profile = Some(value(flag, args.next())?),
+            "--shot-clock" => {
+                let seconds = number(flag, args.next())?;
+                options.shot_clock = Some(Some(seconds).filter(|x| *x > 0));
+            }
+            "--forfeit" => options.expiry = Some(Expiry::Forfeit),
+            "--objects" => options.objects = true,
+            "--ricochet" => options.ricochet = true,
+            "--wind-field" => options.wind_field = true,
+            "--self-hit-penalty" => options.self_hit_penalty = true,
+            "--no-friendly-fire" => options.friendly_fire = false,
+            "--sudden-death" => {
+                let turns = number(flag, args.next())?;
+                options.sudden_death = Some(Some(turns).filter(|x| *x > 0));
+            }
+            "--lava" => options.flood = Some(Flood::Lava),
+            "--gravity" => {
+                let gravity: f32 = number(flag, args.next())?;
+                if !(gravity > 0.0 && gravity <= GRAVITY_MAX) {
+                    return Err(format!(
+                        "--gravity must be above 0 and at most {}",
+                        GRAVITY_MAX
+                    ));
+                }
+                options.gravity = Some(gravity);
+            }
+            "--bounces" => {
+                let bounces = number(flag, args.next())?;
+                if !RICOCHET_BOUNCE_CHOICES.contains(&bounces) {
+                    return Err("--bounces must be 1 to 5".to_string());
+                }
+                options.bounces = Some(bounces);
+            }
+            "--tournament" => {
+                let list = value(flag, args.next())?;
+                options.tournament = list.split(',').map(|x| x.trim().to_string()).collect();
+            }
+            "--format" => options.format = format(flag, args.next())?,
+            "--config" => options.config_path = value(flag, args.next())?,
+            "--window" => options.window = window(flag, args.next())?,
+            "--play" => options.play = true,
+            "--practice" => options.practice = true,
+            "-h" | "--help" => options.help = true,
+            _ => return Err(format!("unknown option: {}", arg)),
+        }
+    }
+    // a program needs a bot side to play
+    if options.left_command.is_some() && options.left == Player::Human {
+        options.left = Player::Bot(StrategyId::default(), Difficulty::Normal);
+    }
+    if options.right_command.is_some() && options.right == Player::Human {
+        options.right = Player::Bot(StrategyId::default(), Difficulty::Normal);
+    }
+    Ok(options)
+}
@@ -0,0 +1,82 @@
+use crate::ballistics::Ballistics;
+use crate::input::Bindings;
+use crate::personality::{self, Personality};
+use crate::practice::Practice;
+use crate::profile::{self, Profile};
+use crate::rules::Rules;
+
+use serde::{Deserialize, Serialize};
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+
+pub const CONFIG_PATH: &str = "gorillas.json";
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioConfig {
+    pub muted: bool,
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    pub music_volume: f32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub audio: AudioConfig,
+    pub bindings: Bindings,
+    // the bots pick from these, a list in the file replaces the built-in ones
+    pub personalities: Vec<Personality>,
+    // the humans pick from these, the first two are the default left and right player
+    pub profiles: Vec<Profile>,
+    // the rules new matches are played with
+    pub rules: Rules,
+    // how the banana flies, see ballistics.rs
+    pub ballistics: Ballistics,
+    pub practice: Practice,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        AudioConfig {
+            muted: false,
+            master_volume: 0.8,
+            sfx_volume: 1.0,
+            music_volume: 0.5,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            audio: AudioConfig::default(),
+            bindings: Bindings::default(),
+            personalities: personality::presets(),
+            profiles: profile::presets(),
+            rules: Rules::default(),
+            ballistics: Ballistics::default(),
+            practice: Practice::default(),
+        }
+    }
+}
+
+impl Config {
+    // a missing or broken config file is not fatal, we just start from the defaults
+    pub fn load(path: &str) -> Self {
+        match File::open(path) {
+            Ok(file) => serde_json::from_reader(BufReader::new(file)).unwrap_or_else(|e| {
+                eprintln!("Failed to parse {}: {}", path, e);
+                Config::default()
+            }),
+            Err(_) => Config::default(),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let file = File::create(path)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+// Pieces of building an explosion cut loose.
+//
+// After a crater the parts of a building are grouped by what touches what, every group that
+// does not reach down to the ground breaks off and falls as one chunk. So does everything
+// above a floor that is less than COLLAPSE_SUPPORT as wide as the widest floor it carries.
+// Small chunks shatter on whatever they land on and dig a small crater of their own, big
+// ones and the ones carrying a gorilla settle as rubble that is part of the building again.
+// Floors that give way are crushed to nothing, so a collapse always has somewhere to fall.
+use crate::game::Side;
+use crate::game_constants::*;
+
+use quicksilver::geom::{Rectangle, Vector};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Debris {
+    pub pieces: Vec<Rectangle>,
+    // the index of the building the chunk came from
+    pub building: usize,
+    // pixels per millisecond, straight down
+    pub speed: f32,
+    // how far the chunk came down so far, to find the pieces on the round surface
+    pub fallen: f32,
+    pub rubble: bool,
+    // gorillas standing on the chunk come down with it
+    pub riders: Vec<Side>,
+}
+
+// sharing an edge is enough, touching at a corner is not
+fn touching(a: &Rectangle, b: &Rectangle) -> bool {
+    let x = (a.pos.x + a.size.x).min(b.pos.x + b.size.x) - a.pos.x.max(b.pos.x);
+    let y = (a.pos.y + a.size.y).min(b.pos.y + b.size.y) - a.pos.y.max(b.pos.y);
+    x >= -DEBRIS_TOUCH && y >= -DEBRIS_TOUCH && (x > DEBRIS_TOUCH || y > DEBRIS_TOUCH)
+}
+
+// the ground is the bottom of the window, or the flood once it covers that
+fn grounded(part: &Rectangle, ground: f32) -> bool {
+    part.pos.y + part.size.y >= ground - DEBRIS_TOUCH
+}
+
+fn area(pieces: &[Rectangle]) -> f32 {
+    pieces.iter().map(|x| x.size.x * x.size.y).sum()
+}
+
+// takes the parts that lost their hold on the ground out of the building, grouped by chunk
+pub fn detach(parts: &mut Vec<Rectangle>, building: usize, ground: f32) -> Vec<Debris> {
+    let mut held = parts
+        .iter()
+        .map(|x| grounded(x, ground))
+        .collect::<Vec<_>>();
+    let mut open = (0..parts.len()).filter(|i| held[*i]).collect::<Vec<_>>();
+    while let Some(i) = open.pop() {
+        for j in 0..parts.len() {
+            if !held[j] && touching(&parts[i], &parts[j]) {
+                held[j] = true;
+                open.push(j);
+            }
+        }
+    }
+    if held.iter().all(|x| *x) {
+        return vec![];
+    }
+
+    let mut loose = vec![];
+    let mut kept = vec![];
+    for (part, held) in parts.drain(..).zip(held) {
+        if held {
+            kept.push(part);
+        } else {
+            loose.push(part);
+        }
+    }
+    *parts = kept;
+
+    // the loose parts fall apart into the groups that still hold together
+    let mut chunks = vec![];
+    while let Some(first) = loose.pop() {
+        let mut pieces = vec![first];
+        let mut i = 0;
+        while i < pieces.len() {
+            let (near, far): (Vec<_>, Vec<_>) =
+                loose.into_iter().partition(|x| touching(&pieces[i], x));
+            pieces.extend(near);
+            loose = far;
+            i += 1;
+        }
+        let rubble = area(&pieces) >= RUBBLE_MIN_AREA;
+        chunks.push(Debris::new(pieces, building, rubble));
+    }
+    chunks
+}
+
+// how wide the parts between two heights are, overlapping parts only count once
+fn support(parts: &[Rectangle], top: f32, bottom: f32) -> f32 {
+    let mut spans = parts
+        .iter()
+        .filter(|x| x.pos.y < bottom && x.pos.y + x.size.y > top)
+        .map(|x| (x.pos.x, x.pos.x + x.size.x))
+        .collect::<Vec<_>>();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut width = 0.0;
+    let mut reached = f32::MIN;
+    for (left, right) in spans {
+        width += (right - reached.max(left)).max(0.0);
+        reached = reached.max(right);
+    }
+    width
+}
+
+// the lowest floors that are too thin to carry what is above them give way, they are crushed
+// and everything above comes down, returns the falling section and the crushed floors
+pub fn collapse(parts: &mut Vec<Rectangle>, building: usize) -> Option<(Debris, Vec<Rectangle>)> {
+    let mut edges = parts
+        .iter()
+        .flat_map(|x| [x.pos.y, x.pos.y + x.size.y])
+        .collect::<Vec<_>>();
+    edges.sort_by(|a, b| b.total_cmp(a));
+    edges.dedup();
+    // from the ground up, with the width of each floor
+    let floors = edges
+        .windows(2)
+        .map(|x| (x[1], x[0], support(parts, x[1], x[0])))
+        .collect::<Vec<_>>();
+    // the widest floor above each one is what it has to carry
+    let mut load = vec![0.0; floors.len()];
+    for i in (1..floors.len()).rev() {
+        load[i - 1] = f32::max(load[i], floors[i].2);
+    }
+    let weak = |i: usize| floors[i].2 < load[i] * COLLAPSE_SUPPORT;
+    let first = (0..floors.len()).find(|i| weak(*i))?;
+    let last = (first..floors.len()).take_while(|i| weak(*i)).last()?;
+    let (top, bottom) = (floors[last].0, floors[first].1);
+
+    let mut section = vec![];
+    let mut kept = vec![];
+    let mut crushed = vec![];
+    for part in parts.drain(..) {
+        let (upper, lower) = (part.pos.y, part.pos.y + part.size.y);
+        if lower <= top {
+            section.push(part);
+        } else if upper >= bottom {
+            kept.push(part);
+        } else {
+            // a part reaching through the floor breaks where the floor is
+            let (x, w) = (part.pos.x, part.size.x);
+            if upper < top {
+                section.push(Rectangle::new((x, upper), (w, top - upper)));
+            }
+            if lower > bottom {
+                kept.push(Rectangle::new((x, bottom), (w, lower - bottom)));
+            }
+            let from = upper.max(top);
+            crushed.push(Rectangle::new((x, from), (w, lower.min(bottom) - from)));
+        }
+    }
+    *parts = kept;
+    Some((Debris::new(section, building, true), crushed))
+}
+
+impl Debris {
+    fn new(pieces: Vec<Rectangle>, building: usize, rubble: bool) -> Self {
+        Debris {
+            pieces,
+            building,
+            speed: 0.0,
+            fallen: 0.0,
+            rubble,
+            riders: vec![],
+        }
+    }
+
+    // how far the chunk can come down before it rests on the parts of a building or the ground
+    pub fn clearance(&self, supports: &[Vec<Rectangle>], ground: f32) -> f32 {
+        let mut clearance = f32::MAX;
+        for piece in self.pieces.iter() {
+            let bottom = piece.pos.y + piece.size.y;
+            clearance = clearance.min(ground - bottom);
+            for support in supports.iter().flatten() {
+                let x = (piece.pos.x + piece.size.x).min(support.pos.x + support.size.x)
+                    - piece.pos.x.max(support.pos.x);
+                if x > DEBRIS_TOUCH && support.pos.y >= bottom - DEBRIS_TOUCH {
+                    clearance = clearance.min(support.pos.y - bottom);
+                }
+            }
+        }
+        clearance.max(0.0)
+    }
+
+    pub fn drop(&mut self, distance: f32) {
+        self.fallen += distance;
+        for piece in self.pieces.iter_mut() {
+            piece.pos.y += distance;
+        }
+    }
+
+    // each piece with where it was before the chunk broke off
+    pub fn sources(&self) -> impl Iterator<Item = (Rectangle, Rectangle)> + '_ {
+        let back = Vector::new(0.0, self.fallen);
+        self.pieces
+            .iter()
+            .map(move |x| (*x, Rectangle::new(x.pos - back, x.size)))
+    }
+
+    // the middle of the bottom edge, where the chunk hits what it lands on
+    pub fn bottom(&self) -> Vector {
+        let left = self.pieces.iter().map(|x| x.pos.x).fold(f32::MAX, f32::min);
+        let right = self
+            .pieces
+            .iter()
+            .map(|x| x.pos.x + x.size.x)
+            .fold(f32::MIN, f32::max);
+        let bottom = self
+            .pieces
+            .iter()
+            .map(|x| x.pos.y + x.size.y)
+            .fold(f32::MIN, f32::max);
+        Vector::new((left + right) / 2.0, bottom)
+    }
+
+    // whether the gorilla stands on top of one of the pieces
+    pub fn carries(&self, gorilla: &Rectangle) -> bool {
+        let feet = gorilla.pos.y + gorilla.size.y;
+        self.pieces.iter().any(|x| {
+            (x.pos.y - feet).abs() <= RIDER_TOLERANCE
+                && x.pos.x < gorilla.pos.x + gorilla.size.x
+                && gorilla.pos.x < x.pos.x + x.size.x
+        })
+    }
+
+    // bigger chunks dig deeper, the crater never gets bigger than a banana's
+    pub fn crater_radius(&self) -> f32 {
+        (area(&self.pieces).sqrt() / 2.0)
+            .clamp(DEBRIS_CRATER_MIN, SHOT_RADIUS * EXPLOSION_DESTROY_SCALE)
+    }
+}
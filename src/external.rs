@@ -0,0 +1,359 @@
+// External bots are programs that talk json over stdin and stdout, one object per line.
+//
+// The command is split into the program and its arguments like a shell would split words:
+// single quotes keep everything up to the next one, double quotes keep everything but a
+// backslash before " or \, and a backslash outside quotes keeps the next character. Nothing
+// else of the shell applies, there are no variables, globs or pipes.
+//
+// Every time the bot has to throw, the game writes a turn:
+//   {"type":"turn","side":"Left","me":RECT,"target":RECT,"buildings":[[RECT,..],..],
+//    "wind":{"x":0.01,"y":0.002},"wind_field":FIELD,"gravity":0.012,"drag":0.0,
+//    "air_ratio":170.0,"landings":[{"x":310.0,"y":420.5},..],"power_max":200,
+//    "speed_ratio":0.006,"tick_ms":16.667}
+// where a RECT is {"pos":{"x":..,"y":..},"size":{"x":..,"y":..}} and y grows downwards.
+// buildings holds the intact parts of every building, landings where the previous
+// shots of this side ended this round. Before every round but the first the game writes
+//   {"type":"round"}
+//
+// The program answers every turn with one line:
+//   {"angle":-45.0,"power":150}
+// The angle is in degrees, 0 throws to the right and -90 straight up. The banana starts
+// with a speed of (cos angle, sin angle) * power * speed_ratio, every tick it moves by
+// speed * tick_ms and then its speed changes by what pushed it at the spot it moved from.
+// With a drag of 0 that is the wind and the gravity. Otherwise the air moves with a speed
+// of wind * air_ratio and the speed gains the gravity minus
+//   (speed - air) * |speed - air| * drag
+// The bots throw without spin, the spin settings never change their flight.
+//
+// FIELD is null when the wind is the same everywhere. Otherwise it is
+//   {"shelter":[RECT,..],"layers":[[200.0,1.4],..],"shadow_length":2.0,"shadow_min":0.2}
+// and the wind at a spot is scaled by the factor of the first layer whose y is below the
+// spot, the last layer for the rest, times the shelter of the whole buildings: a spot
+// below the roof of a building and at most (spot y - roof y) * shadow_length downwind of
+// it gets shadow_min at the wall, rising to 1 at that distance, the least of them counts.
+//
+// An answer that is late, does not parse or is out of range is an illegal move, the
+// classic strategy throws instead. Late is after EXTERNAL_TIMEOUT_MS of play, counted in
+// ticks so the time the game spends paused is not held against the program. After
+// EXTERNAL_STRIKES illegal moves in a row, or when the program exits, the classic
+// strategy plays the rest of the match.
+use crate::game::{Difficulty, Side};
+use crate::game_constants::*;
+use crate::strategy::{Classic, Shot, Strategy, World};
+
+use quicksilver::geom::{Rectangle, Vector};
+use serde::{Deserialize, Serialize};
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Message<'a> {
+    Turn {
+        side: Side,
+        me: Rectangle,
+        target: Rectangle,
+        buildings: &'a [Vec<Rectangle>],
+        wind: Vector,
+        wind_field: Option<Field<'a>>,
+        gravity: f32,
+        drag: f32,
+        air_ratio: f32,
+        landings: &'a [Vector],
+        power_max: i32,
+        speed_ratio: f32,
+        tick_ms: f32,
+    },
+    Round,
+}
+
+#[derive(Serialize)]
+struct Field<'a> {
+    shelter: &'a [Rectangle],
+    layers: &'a [(f32, f32)],
+    shadow_length: f32,
+    shadow_min: f32,
+}
+
+#[derive(Deserialize)]
+struct Answer {
+    angle: f32,
+    power: f32,
+}
+
+struct Process {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+enum Reply {
+    Waiting,
+    Answer(String),
+    Late,
+    Exited,
+}
+
+pub struct External {
+    command: String,
+    process: Option<Process>,
+    // the updates played since the turn was asked, a paused game does not count against it
+    asked: Option<u32>,
+    // block until the answer or the timeout instead of checking once per frame
+    wait: bool,
+    strikes: u32,
+    fallback: Classic,
+}
+
+// the words of a command, quoted as described at the top
+fn split(command: &str) -> Result<Vec<String>, &'static str> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("unclosed quote in command"),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c) if c == '"' || c == '\\' => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err("unclosed quote in command"),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err("unclosed quote in command"),
+                    }
+                }
+            }
+            '\\' => {
+                let c = chars.next().ok_or("command ends with a backslash")?;
+                word.get_or_insert_with(String::new).push(c);
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+impl Process {
+    fn spawn(command: &str) -> io::Result<Self> {
+        let invalid = |e| io::Error::new(io::ErrorKind::InvalidInput, e);
+        let words = split(command).map_err(invalid)?;
+        let (program, args) = words
+            .split_first()
+            .ok_or_else(|| invalid("empty command"))?;
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let (stdin, stdout) = match (child.stdin.take(), child.stdout.take()) {
+            (Some(stdin), Some(stdout)) => (stdin, stdout),
+            _ => return Err(io::Error::other("no pipes to the program")),
+        };
+        // the answers are read on their own thread so a slow bot never blocks a frame
+        let (sender, lines) = mpsc::channel();
+        thread::Builder::new()
+            .name("external bot".to_string())
+            .spawn(move || {
+                for line in BufReader::new(stdout).lines() {
+                    let sent = line.map(|line| sender.send(line).is_ok());
+                    if let Ok(false) | Err(_) = sent {
+                        break;
+                    }
+                }
+            })?;
+        Ok(Process {
+            child,
+            stdin,
+            lines,
+        })
+    }
+
+    fn send(&mut self, message: &Message) -> io::Result<()> {
+        serde_json::to_writer(&mut self.stdin, message)?;
+        self.stdin.write_all(b"\n")?;
+        self.stdin.flush()
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn parse(line: &str) -> Result<Shot, String> {
+    let answer: Answer = serde_json::from_str(line).map_err(|e| e.to_string())?;
+    if !answer.angle.is_finite() {
+        return Err(format!("angle {} is not a number", answer.angle));
+    }
+    if !(0.0..=POWER_MAX as f32).contains(&answer.power) {
+        return Err(format!(
+            "power {} is not within 0..={}",
+            answer.power, POWER_MAX
+        ));
+    }
+    Ok(Shot {
+        dir: Vector::from_angle(answer.angle),
+        power: answer.power.round() as i32,
+    })
+}
+
+impl External {
+    pub fn new(command: &str, difficulty: Difficulty, wait: bool) -> Self {
+        let process = Process::spawn(command)
+            .map_err(|e| eprintln!("Failed to start bot {}: {}", command, e))
+            .ok();
+        External {
+            command: command.to_string(),
+            process,
+            asked: None,
+            wait,
+            strikes: 0,
+            fallback: Classic::new(difficulty),
+        }
+    }
+
+    fn stop(&mut self, reason: &str) {
+        if self.process.take().is_some() {
+            eprintln!("Stopped bot {}: {}", self.command, reason);
+        }
+    }
+
+    fn illegal(&mut self, reason: &str) {
+        eprintln!("Illegal move from bot {}: {}", self.command, reason);
+        self.strikes += 1;
+        if self.strikes >= EXTERNAL_STRIKES {
+            self.stop("too many illegal moves");
+        }
+    }
+
+    fn ask(&mut self, world: &World) {
+        let turn = Message::Turn {
+            side: world.side,
+            me: world.me,
+            target: world.target,
+            buildings: world.buildings,
+            wind: world.wind,
+            wind_field: Some(Field {
+                shelter: world.shelter,
+                layers: &WIND_LAYERS,
+                shadow_length: WIND_SHADOW_LENGTH,
+                shadow_min: WIND_SHADOW_MIN,
+            })
+            .filter(|_| world.wind_field),
+            gravity: world.gravity,
+            drag: world.drag,
+            air_ratio: WIND_AIR_RATIO,
+            landings: world.landings,
+            power_max: POWER_MAX,
+            speed_ratio: SHOT_SPEED_RATIO,
+            tick_ms: DELTAT_MS,
+        };
+        let result = match self.process.as_mut() {
+            Some(process) => {
+                // an answer that came in after its turn timed out is not meant for this one
+                while process.lines.try_recv().is_ok() {}
+                process.send(&turn)
+            }
+            None => return,
+        };
+        match result {
+            Ok(()) => self.asked = Some(0),
+            Err(e) => self.stop(&e.to_string()),
+        }
+    }
+
+    fn reply(&mut self, asked: u32) -> Reply {
+        let process = match self.process.as_mut() {
+            Some(process) => process,
+            None => return Reply::Exited,
+        };
+        if self.wait {
+            let timeout = Duration::from_millis(EXTERNAL_TIMEOUT_MS);
+            return match process.lines.recv_timeout(timeout) {
+                Ok(line) => Reply::Answer(line),
+                Err(RecvTimeoutError::Timeout) => Reply::Late,
+                Err(RecvTimeoutError::Disconnected) => Reply::Exited,
+            };
+        }
+        match process.lines.try_recv() {
+            Ok(line) => Reply::Answer(line),
+            Err(TryRecvError::Empty) if (asked as f32) * DELTAT_MS < EXTERNAL_TIMEOUT_MS as f32 => {
+                Reply::Waiting
+            }
+            Err(TryRecvError::Empty) => Reply::Late,
+            Err(TryRecvError::Disconnected) => Reply::Exited,
+        }
+    }
+}
+
+impl Strategy for External {
+    fn shoot(&mut self, world: &World) -> Option<Shot> {
+        let asked = match self.asked.as_mut() {
+            Some(asked) => {
+                *asked += 1;
+                *asked
+            }
+            None => {
+                self.ask(world);
+                return match self.process {
+                    Some(_) => None,
+                    None => self.fallback.shoot(world),
+                };
+            }
+        };
+
+        let reply = self.reply(asked);
+        if let Reply::Waiting = reply {
+            return None;
+        }
+        self.asked = None;
+        match reply {
+            Reply::Answer(line) => match parse(&line) {
+                Ok(shot) => {
+                    self.strikes = 0;
+                    return Some(shot);
+                }
+                Err(e) => self.illegal(&e),
+            },
+            Reply::Late => self.illegal("no answer in time"),
+            _ => self.stop("the program exited"),
+        }
+        self.fallback.shoot(world)
+    }
+
+    fn new_round(&mut self) {
+        self.asked = None;
+        let result = match self.process.as_mut() {
+            Some(process) => process.send(&Message::Round),
+            None => return,
+        };
+        if let Err(e) = result {
+            self.stop(&e.to_string());
+        }
+    }
+}
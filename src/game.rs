@@ -1,121 +1,452 @@
+use crate::audio::Sfx;
+use crate::ballistics::{Ballistics, Spin};
+use crate::debris::{self, Debris};
+use crate::external::External;
 use crate::game_constants::*;
+use crate::input::*;
+use crate::objects::{self, Object};
+use crate::personality::Personality;
+use crate::practice::Range;
+use crate::profile::Profile;
+use crate::rules::{Expiry, Flood, Rules};
+use crate::strategy::{Shot, Strategy, StrategyId, World};
+use crate::wind;
 use crate::{GameConfig, SharedAssets, SharedData};
 
 use quicksilver::{
     geom::{Circle, Line, Rectangle, Shape, Transform, Vector},
     graphics::{
-        Background::{Col, Img},
+        Background::{Blended, Col, Img},
         Color, Image, Surface, View,
     },
-    input::{ButtonState, MouseButton},
+    input::ButtonState,
     lifecycle::{Event, Window},
     Result,
 };
 
 use rand::prelude::*;
+use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize};
 use std::cmp;
 
-#[derive(Copy, Clone)]
-enum Side {
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Side {
     Left,
     Right,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+// what a human throws, the bots stick to the plain banana
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Weapon {
+    Banana,
+    // bounces off the walls it glances off, see Rules::bounces
+    Bouncy,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Player {
+    Human,
+    Bot(StrategyId, Difficulty),
+}
+
+impl Weapon {
+    pub fn name(self) -> &'static str {
+        match self {
+            Weapon::Banana => "Banana",
+            Weapon::Bouncy => "Bouncy banana",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Weapon::Banana => Weapon::Bouncy,
+            Weapon::Bouncy => Weapon::Banana,
+        }
+    }
+}
+
+// the strategy is rebuilt from the registry or the command after loading a saved game
+#[derive(Serialize, Deserialize)]
 struct Bot {
+    strategy: StrategyId,
+    difficulty: Difficulty,
+    // an external program playing this side instead of the strategy
+    command: Option<String>,
+    #[serde(skip)]
+    headless: bool,
+    personality: Personality,
     counter: i32,
-    dir: Vector,
-    pos: Vector,
-    target: Vector,
+    planned: Option<Shot>,
+    // updates left before the aim starts to turn towards the planned shot
+    thinking: u32,
+    // the aim line on screen, it turns towards the planned shot
+    aim: Option<Vector>,
+    #[serde(skip)]
+    brain: Option<Box<dyn Strategy>>,
+}
+
+// a shot that just ended, for the thrower to say something about
+struct Taunt {
+    side: Side,
+    text: String,
+    frames: u32,
+    image: Option<Image>,
+}
+
+// a line about what the rules did, shown over the field for everyone
+struct Notice {
+    text: String,
+    frames: u32,
+    image: Option<Image>,
 }
 
 enum Collision {
     None,
     Sky,
     Buildings(Vec<usize>),
+    Flood,
+    Target(usize),
     Player(Side, Vec<usize>),
+    Object(usize, Vec<usize>),
 }
 
+// the rendered surface and score are rebuilt after loading a saved game
+#[derive(Serialize, Deserialize)]
 struct Round {
     buildings: Vec<Building>,
     gorilla_left: Rectangle,
     gorilla_right: Rectangle,
     wind: Vector,
     rain: Vec<Vector>,
+    objects: Vec<Object>,
+    // the objects as they were spawned, a restart brings back what was destroyed
+    spawned_objects: Vec<Object>,
+    // chunks of building falling down
+    debris: Vec<Debris>,
+    // turns taken this round, counted for sudden death
+    turns: u32,
+    // the top of the sudden death flood, at the bottom of the window until it rises
+    flood: f32,
+    // the side that lost a point to the self-hit penalty, given back on a restart
+    penalty: Option<Side>,
+    landings_left: Vec<Vector>,
+    landings_right: Vec<Vector>,
+    shots_left: u32,
+    shots_right: u32,
+    frames: u32,
+    #[serde(skip)]
     surface: Option<Surface>,
+    #[serde(skip)]
     score: Option<Image>,
+    #[serde(skip)]
     render_score: bool,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RoundSummary {
+    pub winner: Side,
+    // who threw the banana that ended the round, the winner unless it hit its thrower
+    pub thrower: Side,
+    // from the thrower to where the banana hit
+    pub distance: f32,
+    pub shots_left: u32,
+    pub shots_right: u32,
+    pub frames: u32,
+    // the flood took the loser, nobody's banana did
+    #[serde(default)]
+    pub flooded: bool,
+}
+
+// what changed on the round surface, in order, a later mask is drawn over an earlier one
+#[derive(Clone, Serialize, Deserialize)]
+enum Mask {
+    Hole(Circle),
+    // where a chunk broke off or a floor was crushed
+    Broken(Rectangle),
+    // a piece of a chunk that settled, with where it was on the surface before
+    Rubble(Rectangle, Rectangle),
+}
+
+#[derive(Serialize, Deserialize)]
 struct Explosion {
     pos: Vector,
     frame: u32,
 }
 
+// held aim keys and the stick tilt, rotates the aim every update
+#[derive(Default)]
+struct AimInput {
+    up: bool,
+    down: bool,
+    stick: f32,
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct Game {
     round: Round,
     counting: bool,
     counter: i32,
     explosion_state: Option<Explosion>,
-    explosion_masks: Vec<Circle>,
+    explosion_masks: Vec<Mask>,
     shot: Option<(Circle, Vector, f32)>,
     turn: Side,
     points_left: u32,
     points_right: u32,
     new_game: Option<Side>,
+    #[serde(skip)]
     juice: Option<f32>,
-    mouse_pos: Vector,
+    #[serde(skip)]
+    taunt: Option<Taunt>,
+    #[serde(skip)]
+    notice: Option<Notice>,
+    aim_left: Vector,
+    aim_right: Vector,
+    #[serde(skip)]
+    aim_input: AimInput,
     bot_right: Option<Bot>,
     bot_left: Option<Bot>,
+    // the humans playing, a bot side has none
+    profile_left: Option<Profile>,
+    profile_right: Option<Profile>,
+    // rendered once for the scoreboard
+    #[serde(skip)]
+    names: Option<(Image, Image)>,
+    // first side to reach this many points wins, the match is endless without it
+    match_length: Option<u32>,
+    rules: Rules,
+    // updates left on the shot clock of the current turn
+    clock: u32,
+    #[serde(skip)]
+    clock_image: Option<(u32, Image)>,
+    weapon_left: Weapon,
+    weapon_right: Weapon,
+    spin_left: Spin,
+    spin_right: Spin,
+    // what the banana in the air is, how often it bounced so far and how it turns
+    shot_weapon: Weapon,
+    bounces: u32,
+    shot_turn: f32,
+    #[serde(skip)]
+    weapon_image: Option<(Weapon, Spin, Image)>,
+    ballistics: Ballistics,
+    practice: Option<Range>,
+    // drives the terrain, the wind and the bots, effects use the thread rng. Saved with the
+    // match so a seeded match plays on the same after loading
+    rng: Pcg32,
 }
 
+#[derive(Serialize, Deserialize)]
 struct Building {
     bound_box: Rectangle,
     tiles: Vec<usize>, // indicies into the tilemap
 }
 
+impl Difficulty {
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+}
+
+impl Player {
+    pub fn name(self) -> &'static str {
+        match self {
+            Player::Human => "Human",
+            Player::Bot(_, _) => "Gorilla",
+        }
+    }
+
+    // the order the main menu cycles through
+    pub fn next(self) -> Self {
+        match self {
+            Player::Human => Player::Bot(StrategyId::default(), Difficulty::Easy),
+            Player::Bot(id, Difficulty::Easy) => Player::Bot(id, Difficulty::Normal),
+            Player::Bot(id, Difficulty::Normal) => Player::Bot(id, Difficulty::Hard),
+            Player::Bot(_, Difficulty::Hard) => Player::Human,
+        }
+    }
+
+    pub fn next_strategy(self) -> Self {
+        match self {
+            Player::Bot(id, difficulty) => Player::Bot(id.next(), difficulty),
+            Player::Human => Player::Human,
+        }
+    }
+}
+
 impl Bot {
-    fn new(side: Side, round: &Round) -> Self {
-        let (dir, target, pos) = match side {
-            Side::Left => {
-                let target = round.gorilla_right.center();
-                let pos = round.gorilla_left.center();
-                (target - pos, target, pos)
-            }
-            Side::Right => {
-                let target = round.gorilla_left.center();
-                let pos = round.gorilla_right.center();
-                (target - pos, target, pos)
-            }
-        };
-        let dir = dir.normalize();
+    fn new(
+        strategy: StrategyId,
+        difficulty: Difficulty,
+        command: Option<String>,
+        headless: bool,
+        personality: Personality,
+    ) -> Self {
         Bot {
+            strategy,
+            difficulty,
+            command,
+            headless,
+            personality,
             counter: 0,
-            dir,
-            target,
-            pos,
+            planned: None,
+            thinking: 0,
+            aim: None,
+            brain: None,
+        }
+    }
+
+    fn aim(&self) -> Option<Vector> {
+        self.aim
+    }
+
+    fn new_round(&mut self) {
+        self.counter = 0;
+        self.planned = None;
+        self.thinking = 0;
+        self.aim = None;
+        if let Some(brain) = self.brain.as_mut() {
+            brain.new_round();
         }
     }
 
-    fn aim(&mut self, collision: Vector) {
-        let mut inc = if collision.x < self.target.x {
-            BOT_AIM_INC_Y
+    fn plan(&mut self, world: &World) -> Option<Shot> {
+        let (strategy, difficulty, headless) = (self.strategy, self.difficulty, self.headless);
+        let command = &self.command;
+        let brain = self.brain.get_or_insert_with(|| match command {
+            Some(command) => Box::new(External::new(command, difficulty, headless)),
+            None => strategy.create(difficulty),
+        });
+        let shot = brain.shoot(world)?;
+        let dir = if shot.dir.x.is_finite() && shot.dir.y.is_finite() && shot.dir.len2() > 0.0 {
+            shot.dir.normalize()
         } else {
-            -BOT_AIM_INC_Y
+            (world.target.center() - world.me.center()).normalize()
         };
-        if self.pos.x < self.target.x {
-            inc = -inc;
+        Some(Shot {
+            dir,
+            power: shot.power.clamp(0, POWER_MAX),
+        })
+    }
+
+    // the strategy's shot with the mistakes of the difficulty and the personality in it
+    fn flaw(&self, shot: Shot, rng: &mut Pcg32) -> Shot {
+        let dir = match self.difficulty {
+            Difficulty::Easy => {
+                let noise = rng.gen_range(-BOT_EASY_AIM_NOISE, BOT_EASY_AIM_NOISE);
+                Vector::from_angle(shot.dir.angle() + noise)
+            }
+            _ => shot.dir,
+        };
+        let variance = self.personality.power_variance.clamp(0.0, 1.0);
+        let power = if variance > 0.0 {
+            shot.power as f32 * (1.0 + rng.gen_range(-variance, variance))
+        } else {
+            shot.power as f32
+        };
+        Shot {
+            dir,
+            power: (power.round() as i32).clamp(0, POWER_MAX),
         }
-        self.dir.y += inc;
-        self.dir = self.dir.normalize();
+    }
+
+    // thinks, turns the aim line and charges up to the planned power, then throws
+    fn update(&mut self, world: &World, rng: &mut Pcg32) -> Option<Shot> {
+        let aim = *self.aim.get_or_insert_with(|| rest_aim(world.side));
+        if self.planned.is_none() {
+            let shot = self.plan(world)?;
+            self.planned = Some(self.flaw(shot, rng));
+            let (low, high) = self.personality.think_ms;
+            let ms = if low < high {
+                rng.gen_range(low, high + 1)
+            } else {
+                low
+            };
+            self.thinking = (ms as f32 / DELTAT_MS).round() as u32;
+        }
+        let planned = self.planned?;
+
+        if self.thinking > 0 {
+            self.thinking -= 1;
+            return None;
+        }
+
+        let speed = self.personality.aim_speed.max(0.1);
+        let turn = (planned.dir.angle() - aim.angle() + 540.0) % 360.0 - 180.0;
+        if turn.abs() > speed {
+            self.aim = Some(Vector::from_angle(aim.angle() + speed * turn.signum()));
+            return None;
+        }
+        self.aim = Some(planned.dir);
+
+        let charge = self.personality.charge_speed.max(1);
+        self.counter = cmp::min(self.counter + charge, planned.power);
+        if self.counter < planned.power {
+            return None;
+        }
+        self.counter = 0;
+        self.planned = None;
+        Some(planned)
+    }
+
+    fn name(&self) -> String {
+        let brain = match &self.command {
+            Some(command) => {
+                let program = command.split_whitespace().next().unwrap_or_default();
+                program.rsplit(&['/', '\\'][..]).next().unwrap_or_default()
+            }
+            None => self.strategy.name(),
+        };
+        bot_name(brain, self.difficulty)
+    }
+
+    fn taunt(&self, hit: bool) -> Option<String> {
+        let mut rng = rand::thread_rng();
+        if !rng.gen_bool(self.personality.chattiness.clamp(0.0, 1.0) as f64) {
+            return None;
+        }
+        let lines = match hit {
+            true => &self.personality.hit_taunts,
+            false => &self.personality.miss_taunts,
+        };
+        lines.choose(&mut rng).cloned()
+    }
+}
+
+impl AimInput {
+    fn rate(&self) -> f32 {
+        let mut rate = self.stick;
+        if self.up {
+            rate += 1.0;
+        }
+        if self.down {
+            rate -= 1.0;
+        }
+        rate
     }
 }
 
 impl Round {
-    fn new(pools: &mut [Vec<Rectangle>]) -> Self {
-        let buildings = Building::buildings(pools);
-        let gorilla_left = place_gorilla(Side::Left, &buildings);
-        let gorilla_right = place_gorilla(Side::Right, &buildings);
-        let mut rng = rand::thread_rng();
+    fn new(pools: &mut [Vec<Rectangle>], rules: &Rules, rng: &mut Pcg32) -> Self {
+        let buildings = Building::buildings(pools, rng);
+        let gorilla_left = place_gorilla(Side::Left, &buildings, rng);
+        let gorilla_right = place_gorilla(Side::Right, &buildings, rng);
+        let objects = if rules.objects {
+            let boxes = buildings.iter().map(|x| x.bound_box).collect::<Vec<_>>();
+            objects::spawn(&boxes, [gorilla_left, gorilla_right], rng)
+        } else {
+            vec![]
+        };
         let x = rng.gen_range(-1.0, 1.0);
         let y = rng.gen_range(0.0, 0.25); // do not consider upwards wind
         let strength = rng.gen_range(1.0, 2.0);
@@ -134,6 +465,17 @@ impl Round {
             gorilla_right,
             wind,
             rain,
+            spawned_objects: objects.clone(),
+            objects,
+            debris: vec![],
+            turns: 0,
+            flood: WINDOW_Y,
+            penalty: None,
+            landings_left: vec![],
+            landings_right: vec![],
+            shots_left: 0,
+            shots_right: 0,
+            frames: 0,
             surface: None,
             score: None,
             render_score: true,
@@ -142,9 +484,8 @@ impl Round {
 }
 
 impl Building {
-    fn buildings(pools: &mut [Vec<Rectangle>]) -> Vec<Building> {
+    fn buildings(pools: &mut [Vec<Rectangle>], rng: &mut Pcg32) -> Vec<Building> {
         let mut b = vec![];
-        let mut rng = rand::thread_rng();
         let mut last_x = 0;
         for pool in pools {
             let pos_y = TILE_SIZE.1 * rng.gen_range(16, 32);
@@ -179,18 +520,31 @@ impl Building {
     }
 }
 
-fn update_shot(pos: Vector, speed: Vector) -> (Vector, Vector) {
+fn update_shot(pos: Vector, speed: Vector, gravity: f32) -> (Vector, Vector) {
     (
         Vector::new(pos.x + DELTAT_MS * speed.x, pos.y + DELTAT_MS * speed.y),
-        Vector::new(speed.x, speed.y + GRAVITY),
+        Vector::new(speed.x, speed.y + gravity),
     )
 }
 
-fn update_shot_windy(pos: Vector, speed: Vector, wind: Vector) -> (Vector, Vector) {
-    (
-        Vector::new(pos.x + DELTAT_MS * speed.x, pos.y + DELTAT_MS * speed.y),
-        Vector::new(wind.x + speed.x, wind.y + speed.y + GRAVITY),
-    )
+// what a bot is called in the statistics and the tournaments
+pub fn bot_name(brain: &str, difficulty: Difficulty) -> String {
+    format!("Gorilla ({}, {})", brain, difficulty.name())
+}
+
+// the whole shot clock of a turn in updates, zero without one
+fn shot_clock(rules: &Rules) -> u32 {
+    rules
+        .shot_clock
+        .map_or(0, |x| (x as f32 * 1000.0 / DELTAT_MS).round() as u32)
+}
+
+// where the aim points before anyone touches it
+fn rest_aim(side: Side) -> Vector {
+    match side {
+        Side::Left => Vector::new(1.0, -1.0).normalize(),
+        Side::Right => Vector::new(-1.0, -1.0).normalize(),
+    }
 }
 
 fn next_side(side: Side) -> Side {
@@ -200,9 +554,8 @@ fn next_side(side: Side) -> Side {
     }
 }
 
-fn place_gorilla(side: Side, buildings: &[Building]) -> Rectangle {
+fn place_gorilla(side: Side, buildings: &[Building], rng: &mut Pcg32) -> Rectangle {
     let field_length = buildings.len() - 1;
-    let mut rng = rand::thread_rng();
     let i = match side {
         Side::Left => rng.gen_range(1, (field_length / 2) - DISTANCE_MIN),
         Side::Right => rng.gen_range((field_length / 2) + DISTANCE_MIN, field_length),
@@ -217,6 +570,47 @@ fn place_gorilla(side: Side, buildings: &[Building]) -> Rectangle {
     )
 }
 
+fn world_view<'a>(
+    round: &'a Round,
+    side: Side,
+    parts: &'a [Vec<Rectangle>],
+    shelter: Option<&'a [Rectangle]>,
+    gravity: f32,
+    drag: f32,
+) -> World<'a> {
+    let (me, target, landings) = match side {
+        Side::Left => (
+            round.gorilla_left,
+            round.gorilla_right,
+            &round.landings_left,
+        ),
+        Side::Right => (
+            round.gorilla_right,
+            round.gorilla_left,
+            &round.landings_right,
+        ),
+    };
+    World {
+        side,
+        me,
+        target,
+        buildings: &parts[..round.buildings.len()],
+        wind: round.wind * WIND_PLAY_RATIO,
+        wind_field: shelter.is_some(),
+        shelter: shelter.unwrap_or_default(),
+        gravity,
+        drag,
+        landings,
+    }
+}
+
+fn gorilla_name(side: Side) -> &'static str {
+    match side {
+        Side::Left => "Left gorilla",
+        Side::Right => "Right gorilla",
+    }
+}
+
 fn collide_field(pos: Vector) -> bool {
     pos.x > WINDOW_X || pos.y > WINDOW_Y || pos.x < 0.0
 }
@@ -229,7 +623,7 @@ fn remove_parts(circle: &Circle, parts: &mut Vec<Rectangle>) {
         .collect::<Vec<_>>();
     parts.retain(|x| !circle.overlaps(x));
     for i in overlap.iter() {
-        parts.extend(create_parts(&circle, i));
+        parts.extend(create_parts(circle, i));
     }
 }
 
@@ -272,6 +666,31 @@ fn collide_shot(circle: Circle, buildings: &[Building], parts: &[Vec<Rectangle>]
     v
 }
 
+// the intact piece of building the banana touches
+fn hit_part(circle: Circle, buildings: &[Building], parts: &[Vec<Rectangle>]) -> Option<Rectangle> {
+    buildings
+        .iter()
+        .zip(parts)
+        .filter(|(building, _)| circle.overlaps(&building.bound_box))
+        .flat_map(|(_, parts)| parts.iter())
+        .find(|x| circle.overlaps(*x))
+        .copied()
+}
+
+// the face of the rectangle the point is furthest outside of, the banana came through it
+fn surface_normal(rect: &Rectangle, pos: Vector) -> Vector {
+    let faces = [
+        (rect.pos.x - pos.x, Vector::new(-1.0, 0.0)),
+        (pos.x - rect.pos.x - rect.size.x, Vector::new(1.0, 0.0)),
+        (rect.pos.y - pos.y, Vector::new(0.0, -1.0)),
+        (pos.y - rect.pos.y - rect.size.y, Vector::new(0.0, 1.0)),
+    ];
+    faces
+        .iter()
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map_or(Vector::new(0.0, -1.0), |x| x.1)
+}
+
 fn collide_player(circle: Circle, player: &Rectangle) -> bool {
     circle.overlaps(player)
 }
@@ -305,16 +724,50 @@ fn create_parts(circle: &Circle, source: &Rectangle) -> Vec<Rectangle> {
 
 impl Game {
     pub fn new(config: GameConfig, pools: &mut [Vec<Rectangle>]) -> Result<Self> {
-        let round = Round::new(pools);
-        let bot_left = if config.bot_left {
-            Some(Bot::new(Side::Left, &round))
-        } else {
-            None
+        // the pools may still hold the terrain of an abandoned game
+        for pool in pools.iter_mut() {
+            pool.clear();
+        }
+        let mut rng = match config.seed {
+            Some(seed) => Pcg32::seed_from_u64(seed),
+            None => Pcg32::from_entropy(),
         };
-        let bot_right = if config.bot_right {
-            Some(Bot::new(Side::Right, &round))
-        } else {
-            None
+        let mut rules = config.rules;
+        let mut round = Round::new(pools, &rules, &mut rng);
+        let practice = config.practice.map(|settings| {
+            let boxes = round
+                .buildings
+                .iter()
+                .map(|x| x.bound_box)
+                .collect::<Vec<_>>();
+            Range::new(settings, &boxes, &round.gorilla_left, &mut rng)
+        });
+        // on the range nothing can take the thrower out, its opponent waits outside the field
+        if practice.is_some() {
+            rules.friendly_fire = false;
+            rules.sudden_death = None;
+            round.gorilla_right.pos = Vector::new(-WINDOW_X, -WINDOW_Y);
+        }
+        let bot = |player, command: &Option<String>, personality: &Personality| match player {
+            Player::Bot(strategy, difficulty) => Some(Bot::new(
+                strategy,
+                difficulty,
+                command.clone(),
+                config.headless,
+                personality.clone(),
+            )),
+            Player::Human => None,
+        };
+        let bot_left = bot(config.left, &config.left_command, &config.left_personality);
+        let bot_right = bot(
+            config.right,
+            &config.right_command,
+            &config.right_personality,
+        )
+        .filter(|_| practice.is_none());
+        let profile = |player, profile: &Profile| match player {
+            Player::Human => Some(profile.clone()),
+            Player::Bot(_, _) => None,
         };
 
         Ok(Game {
@@ -329,12 +782,122 @@ impl Game {
             points_right: 0,
             new_game: None,
             juice: None,
-            mouse_pos: Vector::ZERO,
+            taunt: None,
+            notice: None,
+            aim_left: rest_aim(Side::Left),
+            aim_right: rest_aim(Side::Right),
+            aim_input: AimInput::default(),
             bot_left,
             bot_right,
+            profile_left: profile(config.left, &config.left_profile),
+            profile_right: profile(config.right, &config.right_profile)
+                .filter(|_| practice.is_none()),
+            names: None,
+            match_length: config.match_length,
+            rules,
+            clock: shot_clock(&rules),
+            clock_image: None,
+            weapon_left: Weapon::Banana,
+            weapon_right: Weapon::Banana,
+            spin_left: Spin::None,
+            spin_right: Spin::None,
+            shot_weapon: Weapon::Banana,
+            bounces: 0,
+            shot_turn: 0.0,
+            weapon_image: None,
+            ballistics: config.ballistics,
+            practice,
+            rng,
         })
     }
 
+    pub fn building_count(&self) -> usize {
+        self.round.buildings.len()
+    }
+
+    // called once a saved game is loaded back
+    pub fn restored(&mut self) {
+        self.round.render_score = true;
+        self.counting = false;
+    }
+
+    pub fn winner(&self) -> Option<Side> {
+        let length = self.match_length?;
+        if self.points_left >= length {
+            Some(Side::Left)
+        } else if self.points_right >= length {
+            Some(Side::Right)
+        } else {
+            None
+        }
+    }
+
+    // who takes the match if it stops now, an endless match goes to whoever is ahead
+    pub fn result(&self) -> Option<Side> {
+        if self.match_length.is_some() {
+            return self.winner();
+        }
+        match self.points_left.cmp(&self.points_right) {
+            cmp::Ordering::Greater => Some(Side::Left),
+            cmp::Ordering::Less => Some(Side::Right),
+            cmp::Ordering::Equal => None,
+        }
+    }
+
+    pub fn points(&self) -> (u32, u32) {
+        (self.points_left, self.points_right)
+    }
+
+    pub fn rounds_played(&self) -> u32 {
+        self.points_left + self.points_right
+    }
+
+    // the name the statistics of a side are kept under
+    pub fn player_name(&self, side: Side) -> String {
+        let bot = match side {
+            Side::Left => self.bot_left.as_ref(),
+            Side::Right => self.bot_right.as_ref(),
+        };
+        match (bot, self.profile_from_side(side), side) {
+            (Some(bot), _, _) => bot.name(),
+            (None, Some(profile), _) => profile.name.clone(),
+            (None, None, Side::Left) => "Human (left)".to_string(),
+            (None, None, Side::Right) => "Human (right)".to_string(),
+        }
+    }
+
+    fn profile_from_side(&self, side: Side) -> Option<&Profile> {
+        match side {
+            Side::Left => self.profile_left.as_ref(),
+            Side::Right => self.profile_right.as_ref(),
+        }
+    }
+
+    // whether the event came from a device the player whose turn it is plays with
+    pub fn accepts(&self, event: &Event) -> bool {
+        self.profile_from_side(self.turn)
+            .is_none_or(|x| x.input.accepts(event))
+    }
+
+    // the round has a winner and the next one has not begun yet
+    pub fn round_decided(&self) -> bool {
+        self.new_game.is_some()
+    }
+
+    // the last explosion of the match has finished
+    pub fn is_over(&self) -> bool {
+        let decided = match &self.practice {
+            Some(range) => range.cleared(),
+            None => self.winner().is_some(),
+        };
+        decided && self.explosion_state.is_none()
+    }
+
+    // a practice round on the range, it is kept out of the statistics
+    pub fn is_practice(&self) -> bool {
+        self.practice.is_some()
+    }
+
     fn gorilla_from_side(&self, side: Side) -> &Rectangle {
         match side {
             Side::Left => &self.round.gorilla_left,
@@ -342,6 +905,97 @@ impl Game {
         }
     }
 
+    fn gorilla_from_side_mut(&mut self, side: Side) -> &mut Rectangle {
+        match side {
+            Side::Left => &mut self.round.gorilla_left,
+            Side::Right => &mut self.round.gorilla_right,
+        }
+    }
+
+    fn aim_from_side(&self, side: Side) -> Vector {
+        match side {
+            Side::Left => self.aim_left,
+            Side::Right => self.aim_right,
+        }
+    }
+
+    fn aim_from_side_mut(&mut self, side: Side) -> &mut Vector {
+        match side {
+            Side::Left => &mut self.aim_left,
+            Side::Right => &mut self.aim_right,
+        }
+    }
+
+    fn weapon_from_side(&self, side: Side) -> Weapon {
+        match side {
+            Side::Left => self.weapon_left,
+            Side::Right => self.weapon_right,
+        }
+    }
+
+    fn weapon_from_side_mut(&mut self, side: Side) -> &mut Weapon {
+        match side {
+            Side::Left => &mut self.weapon_left,
+            Side::Right => &mut self.weapon_right,
+        }
+    }
+
+    fn spin_from_side(&self, side: Side) -> Spin {
+        match side {
+            Side::Left => self.spin_left,
+            Side::Right => self.spin_right,
+        }
+    }
+
+    fn spin_from_side_mut(&mut self, side: Side) -> &mut Spin {
+        match side {
+            Side::Left => &mut self.spin_left,
+            Side::Right => &mut self.spin_right,
+        }
+    }
+
+    // the settings can change in the middle of a match, from the pause menu
+    pub fn set_ballistics(&mut self, ballistics: Ballistics) {
+        self.ballistics = ballistics;
+        self.weapon_image = None;
+    }
+
+    fn building_boxes(&self) -> Vec<Rectangle> {
+        self.round.buildings.iter().map(|x| x.bound_box).collect()
+    }
+
+    // a banana that glances off a wall goes back to where it was with the reflected speed,
+    // the part of the speed into the wall is damped by the restitution, the rest by friction
+    fn bounces_off(
+        &mut self,
+        circle: Circle,
+        from: Vector,
+        speed: Vector,
+        parts: &[Vec<Rectangle>],
+    ) -> bool {
+        let bouncy = self.rules.ricochet || self.shot_weapon == Weapon::Bouncy;
+        if !bouncy || self.bounces >= self.rules.bounces || speed.len2() == 0.0 {
+            return false;
+        }
+        let part = match hit_part(circle, &self.round.buildings, parts) {
+            Some(part) => part,
+            None => return false,
+        };
+        let normal = surface_normal(&part, from);
+        let into = speed.dot(normal);
+        // steep hits go in, and a banana already leaving the wall is let go
+        if into >= 0.0 || -into / speed.len() > RICOCHET_MAX_ANGLE.to_radians().sin() {
+            return false;
+        }
+        let across = normal * into;
+        let along = speed - across;
+        let speed = along * (1.0 - RICOCHET_FRICTION) - across * RICOCHET_RESTITUTION;
+        let angle = self.shot.map_or(0.0, |x| x.2);
+        self.shot = Some((Circle::new(from, circle.radius), speed, angle));
+        self.bounces += 1;
+        true
+    }
+
     fn collision(&self, circle: Circle, parts: &[Vec<Rectangle>]) -> Collision {
         let hits = collide_buildings(circle, &self.round.buildings, parts);
         let explosion = Circle::new(circle.pos, circle.radius * 4.0);
@@ -349,6 +1003,18 @@ impl Game {
             Collision::Buildings(collide_shot(explosion, &self.round.buildings, parts))
         } else if collide_field(circle.pos) {
             Collision::Sky
+        } else if circle.pos.y > self.round.flood {
+            Collision::Flood
+        } else if let Some(i) = self.practice.as_ref().and_then(|x| x.hit(&circle)) {
+            Collision::Target(i)
+        } else if let Some(i) = self
+            .round
+            .objects
+            .iter()
+            .position(|x| circle.overlaps(&x.area))
+        {
+            let terrain_damage = collide_shot(explosion, &self.round.buildings, parts);
+            Collision::Object(i, terrain_damage)
         } else if collide_player(circle, &self.round.gorilla_left) {
             let terrain_damage = collide_shot(explosion, &self.round.buildings, parts);
             Collision::Player(Side::Left, terrain_damage)
@@ -382,12 +1048,250 @@ impl Game {
         self.juice = Some(0.0);
     }
 
+    // a crater of a few overlapping holes, a big one in the middle and smaller ones at its edge
     fn destroy_terrain(&mut self, circle: &Circle, xs: Vec<usize>, parts: &mut [Vec<Rectangle>]) {
-        let explosion = Circle::new(circle.pos, circle.radius * EXPLOSION_DESTROY_SCALE);
+        let radius = circle.radius * EXPLOSION_DESTROY_SCALE;
+        let jitter = self.rng.gen_range(CRATER_JITTER.0, CRATER_JITTER.1);
+        let mut holes = vec![Circle::new(circle.pos, radius * jitter)];
+        for _ in 0..self.rng.gen_range(CRATER_LOBES.0, CRATER_LOBES.1 + 1) {
+            let dir = Vector::from_angle(self.rng.gen_range(0.0, 360.0));
+            let size = radius
+                * self
+                    .rng
+                    .gen_range(CRATER_LOBE_RADIUS.0, CRATER_LOBE_RADIUS.1);
+            holes.push(Circle::new(circle.pos + dir * (radius - size / 2.0), size));
+        }
+        self.dig(&holes, xs, parts);
+    }
+
+    // takes the holes out of the buildings, what they cut loose starts falling
+    fn dig(&mut self, holes: &[Circle], mut xs: Vec<usize>, parts: &mut [Vec<Rectangle>]) {
+        for hole in holes {
+            xs.extend(collide_shot(*hole, &self.round.buildings, parts));
+        }
+        xs.sort_unstable();
+        xs.dedup();
+        for i in xs.iter().copied() {
+            for hole in holes {
+                remove_parts(hole, &mut parts[i]);
+            }
+        }
+        self.round
+            .objects
+            .retain(|x| !x.destructible() || !holes.iter().any(|h| h.overlaps(&x.area)));
+        self.explosion_masks
+            .extend(holes.iter().map(|x| Mask::Hole(*x)));
         for i in xs {
-            remove_parts(&explosion, &mut parts[i]);
+            self.loosen(i, parts);
+        }
+    }
+
+    // breaks off what lost its hold on the ground and what a thin floor can no longer carry
+    fn loosen(&mut self, i: usize, parts: &mut [Vec<Rectangle>]) {
+        let ground = self.round.flood;
+        let mut chunks = debris::detach(&mut parts[i], i, ground);
+        while let Some((chunk, crushed)) = debris::collapse(&mut parts[i], i) {
+            self.explosion_masks
+                .extend(crushed.into_iter().map(Mask::Broken));
+            chunks.push(chunk);
+            // what is left under the crushed floor may have lost its hold too
+            chunks.extend(debris::detach(&mut parts[i], i, ground));
+        }
+        for mut chunk in chunks {
+            for side in [Side::Left, Side::Right].iter().copied() {
+                if chunk.carries(self.gorilla_from_side(side)) {
+                    chunk.riders.push(side);
+                    chunk.rubble = true;
+                }
+            }
+            self.explosion_masks
+                .extend(chunk.pieces.iter().map(|x| Mask::Broken(*x)));
+            self.round.debris.push(chunk);
+        }
+    }
+
+    // small chunks shatter on the first thing below them and take a bite out of it, rubble
+    // settles on what it lands on and the gorillas riding it come down with it, what comes
+    // down into the flood is gone
+    fn update_debris(&mut self, data: &mut SharedData) {
+        let gravity = self.rules.gravity_push();
+        let mut i = 0;
+        while i < self.round.debris.len() {
+            let standing = &data.parts[..self.round.buildings.len()];
+            let chunk = &mut self.round.debris[i];
+            chunk.speed += gravity;
+            let clearance = chunk.clearance(standing, self.round.flood);
+            let distance = (chunk.speed * DELTAT_MS).min(clearance);
+            chunk.drop(distance);
+            for side in chunk.riders.clone() {
+                self.gorilla_from_side_mut(side).pos.y += distance;
+            }
+
+            let chunk = &self.round.debris[i];
+            let touches = |area: &Rectangle| chunk.pieces.iter().any(|x| x.overlaps(area));
+            let gorilla = [Side::Left, Side::Right]
+                .iter()
+                .copied()
+                .find(|x| !chunk.riders.contains(x) && touches(self.gorilla_from_side(*x)));
+            let landed = distance >= clearance;
+            let sunk = landed && chunk.bottom().y >= self.round.flood - DEBRIS_TOUCH;
+            let shattered = gorilla.is_some()
+                || (!chunk.rubble && landed)
+                || (chunk.riders.is_empty() && self.round.objects.iter().any(|x| touches(&x.area)));
+            if !landed && !shattered {
+                i += 1;
+                continue;
+            }
+            let chunk = self.round.debris.remove(i);
+            data.sounds.push(Sfx::BuildingHit);
+            match gorilla.filter(|_| self.new_game.is_none()) {
+                Some(side) if self.spares(side) => self.notify(FRIENDLY_FIRE_OFF.to_string()),
+                Some(side) => {
+                    let pos = self.gorilla_from_side(side).center();
+                    self.hit_gorilla(side, pos, data);
+                }
+                None => (),
+            }
+            if sunk && self.round.flood < WINDOW_Y {
+                continue;
+            } else if shattered {
+                let hole = Circle::new(chunk.bottom(), chunk.crater_radius());
+                self.dig(&[hole], vec![], &mut data.parts);
+            } else {
+                self.explosion_masks
+                    .extend(chunk.sources().map(|(x, source)| Mask::Rubble(x, source)));
+                data.parts[chunk.building].extend(chunk.pieces);
+                // the pile may be standing on too little itself
+                self.loosen(chunk.building, &mut data.parts);
+            }
+        }
+    }
+
+    // the thrower's own banana and what it brought down can not hurt it without friendly fire
+    fn spares(&self, side: Side) -> bool {
+        side == self.turn && !self.rules.friendly_fire
+    }
+
+    fn notify(&mut self, text: String) {
+        if self.notice.as_ref().is_some_and(|x| x.text == text) {
+            return;
+        }
+        self.notice = Some(Notice {
+            text,
+            frames: (NOTICE_MS / DELTAT_MS) as u32,
+            image: None,
+        });
+    }
+
+    // the side that was hit loses the round, whatever hit it
+    fn hit_gorilla(&mut self, side: Side, pos: Vector, data: &mut SharedData) {
+        self.lose_round(side, pos, false, data);
+        if side == self.turn {
+            let points = match side {
+                Side::Left => &mut self.points_left,
+                Side::Right => &mut self.points_right,
+            };
+            // a side without points has nothing to lose
+            if self.rules.self_hit_penalty && *points > 0 {
+                *points -= 1;
+                self.round.penalty = Some(side);
+                self.notify(format!(
+                    "{} hit itself and loses a point!",
+                    gorilla_name(side)
+                ));
+            } else {
+                self.notify(format!("{} hit itself!", gorilla_name(side)));
+            }
+        }
+        self.say(side != self.turn);
+    }
+
+    fn lose_round(&mut self, side: Side, pos: Vector, flooded: bool, data: &mut SharedData) {
+        match side {
+            Side::Left => self.points_right += 1,
+            Side::Right => self.points_left += 1,
+        }
+        let gorilla = self.gorilla_from_side(side).center();
+        self.on_explode(data, pos, Some(gorilla));
+        self.new_game = Some(next_side(side));
+        self.round.render_score = true;
+        let thrower = self.gorilla_from_side(self.turn).center();
+        data.rounds.push(RoundSummary {
+            winner: next_side(side),
+            thrower: self.turn,
+            distance: thrower.distance(pos),
+            shots_left: self.round.shots_left,
+            shots_right: self.round.shots_right,
+            frames: self.round.frames,
+            flooded,
+        });
+        data.sounds.push(Sfx::GorillaHit);
+    }
+
+    // the thrower keeps the turn on the range
+    fn pass_turn(&mut self) {
+        if self.practice.is_none() {
+            self.turn = next_side(self.turn);
+        }
+        self.round.turns += 1;
+        if self.rules.sudden_death == Some(self.round.turns) {
+            self.notify(format!(
+                "Sudden death, the {} is rising!",
+                self.rules.flood.name()
+            ));
+        }
+    }
+
+    // after the sudden death turn the flood rises by FLOOD_STEP every turn, taking the
+    // terrain and the gorillas it reaches
+    fn update_flood(&mut self, data: &mut SharedData) {
+        let turns = match self.rules.sudden_death {
+            Some(start) if self.round.turns >= start => self.round.turns + 1 - start,
+            _ => return,
+        };
+        let target = WINDOW_Y - turns as f32 * FLOOD_STEP;
+        if self.round.flood <= target {
+            return;
+        }
+        self.round.flood = (self.round.flood - FLOOD_SPEED * DELTAT_MS).max(target);
+        let level = self.round.flood;
+
+        for i in 0..self.round.buildings.len() {
+            let parts = &mut data.parts[i];
+            if parts.iter().all(|x| x.pos.y + x.size.y <= level) {
+                continue;
+            }
+            let count = parts.len();
+            parts.retain(|x| x.pos.y < level);
+            for part in parts.iter_mut() {
+                part.size.y = part.size.y.min(level - part.pos.y);
+            }
+            // a cut part still stands on the flood, only one that went under can leave
+            // something above it without a hold
+            if parts.len() < count {
+                self.loosen(i, &mut data.parts);
+            }
+        }
+        self.round
+            .objects
+            .retain(|x| !x.destructible() || x.area.pos.y + x.area.size.y < level);
+
+        if self.new_game.is_some() {
+            return;
+        }
+        // the side on turn goes under first when the flood reaches both at once
+        for side in [self.turn, next_side(self.turn)].iter().copied() {
+            let gorilla = *self.gorilla_from_side(side);
+            if gorilla.pos.y + gorilla.size.y > level + FLOOD_REACH {
+                self.lose_round(side, gorilla.center(), true, data);
+                let fate = match self.rules.flood {
+                    Flood::Water => "is washed away",
+                    Flood::Lava => "is burnt up",
+                };
+                self.notify(format!("{} {}!", gorilla_name(side), fate));
+                return;
+            }
         }
-        self.explosion_masks.push(explosion);
     }
 
     pub fn draw(
@@ -398,10 +1302,15 @@ impl Game {
     ) -> Result<()> {
         if self.round.render_score {
             shared.font.borrow_mut().execute(|f| {
-                if let Ok(text) = f.render(
-                    &format!("{:02}-{:02}", self.points_left, self.points_right),
-                    &shared.default_style,
-                ) {
+                // the range shows how the practice goes instead of the points
+                let rendered = match &self.practice {
+                    Some(range) => f.render(&range.score(), &shared.small_style),
+                    None => f.render(
+                        &format!("{:02}-{:02}", self.points_left, self.points_right),
+                        &shared.default_style,
+                    ),
+                };
+                if let Ok(text) = rendered {
                     self.round.render_score = false;
                     self.round.score = Some(text);
                 }
@@ -417,7 +1326,7 @@ impl Game {
                     w.clear(Color::BLACK)?;
                     w.draw_ex(
                         &Rectangle::new_sized(w.screen_size()),
-                        Img(&sky),
+                        Img(sky),
                         Transform::IDENTITY,
                         0.0,
                     );
@@ -463,25 +1372,108 @@ impl Game {
             );
         }
 
+        let surface = self.round.surface.as_ref().map(|x| x.image());
         shared.sky.borrow_mut().execute(|sky| {
             //draw sky
-            for mask in self.explosion_masks.iter() {
+            // the batch sorts by z before the image, every mask gets its own z to stay in order
+            let step = MASK_Z_RANGE / self.explosion_masks.len().max(1) as f32;
+            for (i, mask) in self.explosion_masks.iter().enumerate() {
+                let z = 2.0 + i as f32 * step;
+                match mask {
+                    Mask::Hole(circle) => window.draw_ex(
+                        circle,
+                        Img(&sky.subimage(circle.bounding_box())),
+                        Transform::IDENTITY,
+                        z,
+                    ),
+                    Mask::Broken(area) => {
+                        window.draw_ex(area, Img(&sky.subimage(*area)), Transform::IDENTITY, z)
+                    }
+                    Mask::Rubble(area, source) => {
+                        if let Some(surface) = surface {
+                            let img = surface.subimage(*source);
+                            window.draw_ex(area, Img(&img), Transform::IDENTITY, z);
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })?;
+
+        // falling chunks are cut out of the surface as it was drawn
+        if let Some(surface) = surface {
+            for (area, source) in self.round.debris.iter().flat_map(|x| x.sources()) {
+                let img = surface.subimage(source);
+                window.draw_ex(&area, Img(&img), Transform::IDENTITY, 2.5);
+            }
+        }
+
+        if let Some(range) = &self.practice {
+            let color = Color::WHITE.with_alpha(PRACTICE_TRAIL_ALPHA);
+            for point in range.trails.iter().flatten() {
                 window.draw_ex(
-                    mask,
-                    Img(&sky.subimage(mask.bounding_box())),
+                    &Circle::new(*point, 1.5),
+                    Col(color),
                     Transform::IDENTITY,
-                    2.0,
+                    2.6,
                 );
             }
-            Ok(())
-        })?;
+            for target in range.targets.iter() {
+                let center = target.area.pos;
+                let radius = target.area.radius;
+                window.draw_ex(
+                    &target.area,
+                    Col(Color::from_hex(PRACTICE_TARGET_COLOR)),
+                    Transform::IDENTITY,
+                    3.0,
+                );
+                window.draw_ex(
+                    &Circle::new(center, radius * 0.6),
+                    Col(Color::WHITE),
+                    Transform::IDENTITY,
+                    3.1,
+                );
+                window.draw_ex(
+                    &Circle::new(center, radius * 0.25),
+                    Col(Color::from_hex(PRACTICE_TARGET_COLOR)),
+                    Transform::IDENTITY,
+                    3.2,
+                );
+            }
+        }
+
+        // the sudden death flood covers what it took, the gorillas and bananas in it too
+        if self.round.flood < WINDOW_Y {
+            let (color, edge) = match self.rules.flood {
+                Flood::Water => (FLOOD_WATER, FLOOD_WATER_EDGE),
+                Flood::Lava => (FLOOD_LAVA, FLOOD_LAVA_EDGE),
+            };
+            let top = self.round.flood;
+            window.draw_ex(
+                &Rectangle::new((0.0, top), (WINDOW_X, WINDOW_Y - top)),
+                Col(Color::from_hex(color).with_alpha(FLOOD_ALPHA)),
+                Transform::IDENTITY,
+                4.5,
+            );
+            window.draw_ex(
+                &Line::new((0.0, top), (WINDOW_X, top)).with_thickness(2.0),
+                Col(Color::from_hex(edge)),
+                Transform::IDENTITY,
+                4.5,
+            );
+        }
 
         shared.building_tiles.borrow_mut().execute(|img| {
             // draw shot
             if let Some((circle, _, angle)) = self.shot {
+                let banana = img.subimage(Rectangle::new(BANANA_LOC, BANANA_SIZE));
+                let background = match self.shot_weapon {
+                    Weapon::Banana => Img(&banana),
+                    Weapon::Bouncy => Blended(&banana, Color::from_hex(BOUNCY_TINT)),
+                };
                 window.draw_ex(
                     &circle.bounding_box(),
-                    Img(&img.subimage(Rectangle::new(BANANA_LOC, BANANA_SIZE))),
+                    background,
                     Transform::rotate(angle),
                     3.0,
                 );
@@ -491,11 +1483,12 @@ impl Game {
             {
                 // draw aim
                 let center = self.gorilla_from_side(self.turn).center();
-                let dir = match (self.turn, &self.bot_left, &self.bot_right) {
-                    (Side::Left, Some(bot), _) => bot.dir,
-                    (Side::Right, _, Some(bot)) => bot.dir,
-                    _ => (self.mouse_pos - center).normalize(),
+                let bot_aim = match (self.turn, &self.bot_left, &self.bot_right) {
+                    (Side::Left, Some(bot), _) => bot.aim(),
+                    (Side::Right, _, Some(bot)) => bot.aim(),
+                    _ => None,
                 };
+                let dir = bot_aim.unwrap_or_else(|| self.aim_from_side(self.turn));
                 window.draw_ex(
                     &Line::new(center + (dir * START_OFFSET), center + (dir * END_OFFSET))
                         .with_thickness(4.0),
@@ -508,37 +1501,39 @@ impl Game {
             Ok(())
         })?;
 
-        shared.player_tiles.borrow_mut().execute(|img| {
-            // draw gorillas
-            let (index_left, index_right) = match (self.turn, self.shot_in_progress()) {
-                (_, false) => (0, 0),
-                (Side::Left, true) => (1, 0),
-                (Side::Right, true) => (0, 1),
-            };
+        for object in self.round.objects.iter() {
+            window.draw_ex(&object.area, Col(object.color()), Transform::IDENTITY, 3.0);
+        }
 
-            let mut draw_gorilla = |side, index: u32| {
+        // draw gorillas, each in the skin and tint of its player
+        let (index_left, index_right) = match (self.turn, self.shot_in_progress()) {
+            (_, false) => (0, 0),
+            (Side::Left, true) => (1, 0),
+            (Side::Right, true) => (0, 1),
+        };
+        let gorillas = match self.new_game {
+            None => vec![(Side::Left, index_left), (Side::Right, index_right)],
+            Some(Side::Left) => vec![(Side::Left, index_left)],
+            Some(Side::Right) => vec![(Side::Right, index_right)],
+        };
+        for (side, index) in gorillas {
+            let (skin, tint) = self
+                .profile_from_side(side)
+                .map_or((0, Color::WHITE), |x| (x.skin_index(), x.color()));
+            let gorilla = self.gorilla_from_side(side);
+            shared.player_tiles[skin].borrow_mut().execute(|img| {
                 window.draw_ex(
-                    self.gorilla_from_side(side),
-                    Img(&img.subimage(Rectangle::new((GORILLA_SIZE.0 * index, 0), GORILLA_SIZE))),
+                    gorilla,
+                    Blended(
+                        &img.subimage(Rectangle::new((GORILLA_SIZE.0 * index, 0), GORILLA_SIZE)),
+                        tint,
+                    ),
                     Transform::IDENTITY,
                     3.0,
                 );
-            };
-
-            match self.new_game {
-                None => {
-                    draw_gorilla(Side::Left, index_left);
-                    draw_gorilla(Side::Right, index_right);
-                }
-                Some(Side::Left) => {
-                    draw_gorilla(Side::Left, index_left);
-                }
-                Some(Side::Right) => {
-                    draw_gorilla(Side::Right, index_right);
-                }
-            }
-            Ok(())
-        })?;
+                Ok(())
+            })?;
+        }
 
         let power = match (self.turn, self.bot_left.as_ref(), self.bot_right.as_ref()) {
             (Side::Left, Some(bot), _) => bot.counter,
@@ -589,6 +1584,29 @@ impl Game {
 
         let wind_norm = self.round.wind.normalize();
 
+        // faint arrows over the field while the next throw is lined up
+        if self.rules.wind_field && !self.shot_in_progress() && !self.is_over() {
+            let boxes = self.building_boxes();
+            let color = Color::WHITE.with_alpha(WIND_GRID_ALPHA);
+            let (columns, rows) = ((WINDOW_X / WIND_GRID) as u32, (WINDOW_Y / WIND_GRID) as u32);
+            for (x, y) in (0..columns).flat_map(|x| (0..rows).map(move |y| (x, y))) {
+                let pos = Vector::new(x as f32 + 0.5, y as f32 + 0.5) * WIND_GRID;
+                let blow = wind::at(pos, self.round.wind, &boxes) * WIND_SHOW_RATIO;
+                window.draw_ex(
+                    &Line::new(pos - blow, pos + blow).with_thickness(2.0),
+                    Col(color),
+                    Transform::IDENTITY,
+                    5.0,
+                );
+                window.draw_ex(
+                    &Circle::new(pos + blow, 2.0),
+                    Col(color),
+                    Transform::IDENTITY,
+                    5.0,
+                );
+            }
+        }
+
         for &drop in self.round.rain.iter() {
             window.draw_ex(
                 &Line::new(drop, drop + wind_norm * WIND_SHOW_RATIO).with_thickness(2.0),
@@ -597,6 +1615,26 @@ impl Game {
                 6.0,
             );
         }
+        let banner = match (&self.practice, self.winner()) {
+            (Some(_), _) => Some("Range cleared!"),
+            (None, Some(Side::Left)) => Some("Left gorilla wins!"),
+            (None, Some(Side::Right)) => Some("Right gorilla wins!"),
+            (None, None) => None,
+        };
+        if let Some(banner) = banner.filter(|_| self.is_over()) {
+            shared.font.borrow_mut().execute(|f| {
+                if let Ok(ref text) = f.render(banner, &shared.default_style) {
+                    window.draw_ex(
+                        &text.area().with_center((WINDOW_X / 2.0, WINDOW_Y / 3.0)),
+                        Img(text),
+                        Transform::IDENTITY,
+                        6.0,
+                    );
+                }
+                Ok(())
+            })?;
+        }
+
         if let Some(text) = self.round.score.as_ref() {
             window.draw_ex(
                 &text.area().with_center((WINDOW_X / 2.0, 100.0)),
@@ -606,148 +1644,545 @@ impl Game {
             );
         }
 
+        // the players' names on either side of the score
+        if self.names.is_none() && self.practice.is_none() {
+            let (left, right) = (self.player_name(Side::Left), self.player_name(Side::Right));
+            shared.font.borrow_mut().execute(|f| {
+                if let (Ok(left), Ok(right)) = (
+                    f.render(&left, &shared.small_style),
+                    f.render(&right, &shared.small_style),
+                ) {
+                    self.names = Some((left, right));
+                }
+                Ok(())
+            })?;
+        }
+        if let Some((left, right)) = self.names.as_ref() {
+            // the left name ends and the right one starts a little away from the score
+            let y = 100.0 - left.area().size.y / 2.0;
+            let left_x = WINDOW_X / 2.0 - SCORE_NAME_GAP - left.area().size.x;
+            let right_x = WINDOW_X / 2.0 + SCORE_NAME_GAP;
+            for (text, x) in [(left, left_x), (right, right_x)] {
+                window.draw_ex(
+                    &Rectangle::new((x, y), text.area().size),
+                    Img(text),
+                    Transform::IDENTITY,
+                    4.0,
+                );
+            }
+        }
+
+        // what the human about to throw has in hand
+        if self.human_turn() && !self.shot_in_progress() && !self.is_over() {
+            let weapon = self.weapon_from_side(self.turn);
+            let spin = self.spin_from_side(self.turn);
+            if self
+                .weapon_image
+                .as_ref()
+                .is_none_or(|x| x.0 != weapon || x.1 != spin)
+            {
+                // the spin only matters with the spin setting on
+                let text = if self.ballistics.magnus > 0.0 {
+                    format!("{}, {}", weapon.name(), spin.name())
+                } else {
+                    weapon.name().to_string()
+                };
+                shared.font.borrow_mut().execute(|f| {
+                    self.weapon_image = f
+                        .render(&text, &shared.small_style)
+                        .ok()
+                        .map(|x| (weapon, spin, x));
+                    Ok(())
+                })?;
+            }
+            if let Some((_, _, text)) = self.weapon_image.as_ref() {
+                window.draw_ex(
+                    &Rectangle::new((100.0, WEAPON_Y - text.area().size.y), text.area().size),
+                    Img(text),
+                    Transform::IDENTITY,
+                    4.0,
+                );
+            }
+        }
+
+        // the seconds left on the shot clock, red for the last few
+        if self.clock_running() {
+            let seconds = (self.clock as f32 * DELTAT_MS / 1000.0).ceil() as u32;
+            if self.clock_image.as_ref().is_none_or(|x| x.0 != seconds) {
+                let style = if seconds <= SHOT_CLOCK_WARNING {
+                    &shared.small_hoover_style
+                } else {
+                    &shared.small_style
+                };
+                shared.font.borrow_mut().execute(|f| {
+                    self.clock_image = f
+                        .render(&seconds.to_string(), style)
+                        .ok()
+                        .map(|x| (seconds, x));
+                    Ok(())
+                })?;
+            }
+            if let Some((_, text)) = self.clock_image.as_ref() {
+                window.draw_ex(
+                    &text.area().with_center((WINDOW_X / 2.0, SHOT_CLOCK_Y)),
+                    Img(text),
+                    Transform::IDENTITY,
+                    4.0,
+                );
+            }
+        }
+
+        if let Some(notice) = self.notice.as_mut() {
+            if notice.image.is_none() {
+                shared.font.borrow_mut().execute(|f| {
+                    notice.image = f.render(&notice.text, &shared.small_style).ok();
+                    Ok(())
+                })?;
+            }
+            if let Some(text) = notice.image.as_ref() {
+                window.draw_ex(
+                    &text.area().with_center((WINDOW_X / 2.0, NOTICE_Y)),
+                    Img(text),
+                    Transform::IDENTITY,
+                    6.0,
+                );
+            }
+        }
+
+        // the taunt floats above whoever said it, kept inside the window
+        let speaker = self.taunt.as_ref().map(|x| *self.gorilla_from_side(x.side));
+        if let (Some(taunt), Some(gorilla)) = (self.taunt.as_mut(), speaker) {
+            if taunt.image.is_none() {
+                shared.font.borrow_mut().execute(|f| {
+                    taunt.image = f.render(&taunt.text, &shared.small_style).ok();
+                    Ok(())
+                })?;
+            }
+            if let Some(text) = taunt.image.as_ref() {
+                let half = text.area().size / 2;
+                let x = gorilla.center().x.clamp(half.x, WINDOW_X - half.x);
+                let y = (gorilla.pos.y - TAUNT_OFFSET).max(half.y);
+                window.draw_ex(
+                    &text.area().with_center((x, y)),
+                    Img(text),
+                    Transform::IDENTITY,
+                    6.0,
+                );
+            }
+        }
+
         Ok(())
     }
 
-    fn event_player(&mut self, event: &Event) {
-        match (event, self.counting, self.shot_in_progress()) {
-            (Event::MouseMoved(pos), _, _) => self.mouse_pos = *pos,
-            (Event::MouseButton(MouseButton::Left, ButtonState::Pressed), false, false) => {
+    fn event_player(&mut self, command: Command, sounds: &mut Vec<Sfx>) {
+        match (command, self.counting, self.shot_in_progress()) {
+            (Command::PointerMoved(pos), _, _) => {
+                let center = self.gorilla_from_side(self.turn).center();
+                *self.aim_from_side_mut(self.turn) = (pos - center).normalize();
+            }
+            (Command::AimAxis(tilt), _, _) => self.aim_input.stick = tilt,
+            (Command::Action(Action::AimUp, state), _, _) => self.aim_input.up = state.is_down(),
+            (Command::Action(Action::AimDown, state), _, _) => {
+                self.aim_input.down = state.is_down()
+            }
+            (Command::Action(Action::Weapon, ButtonState::Pressed), false, false) => {
+                let weapon = self.weapon_from_side_mut(self.turn);
+                *weapon = weapon.next();
+                sounds.push(Sfx::MenuMove);
+            }
+            (Command::Action(Action::Spin, ButtonState::Pressed), false, false)
+                if self.ballistics.magnus > 0.0 =>
+            {
+                let spin = self.spin_from_side_mut(self.turn);
+                *spin = spin.next();
+                sounds.push(Sfx::MenuMove);
+            }
+            (Command::Action(Action::Fire, ButtonState::Pressed), false, false) => {
                 self.counting = true;
             }
-            (Event::MouseButton(MouseButton::Left, ButtonState::Released), true, false) => {
-                let center = self.gorilla_from_side(self.turn).center();
-                let dir = (self.mouse_pos - center).normalize();
-                self.counting = false;
-                self.shot = Some((
-                    Circle::new(center + dir * 6 * SHOT_RADIUS, SHOT_RADIUS),
-                    dir * 0.006 * self.counter as f32,
-                    0.0,
-                ));
+            (Command::Action(Action::Fire, ButtonState::Released), true, false) => {
+                self.throw_player(sounds)
             }
             _ => (),
         }
     }
 
-    pub fn event(&mut self, event: &Event) {
+    fn throw_player(&mut self, sounds: &mut Vec<Sfx>) {
+        let center = self.gorilla_from_side(self.turn).center();
+        let dir = self.aim_from_side(self.turn);
+        self.counting = false;
+        self.shot = Some((
+            Circle::new(center + dir * 6 * SHOT_RADIUS, SHOT_RADIUS),
+            dir * SHOT_SPEED_RATIO * self.counter as f32,
+            0.0,
+        ));
+        self.count_shot(self.weapon_from_side(self.turn));
+        if self.ballistics.magnus > 0.0 {
+            self.shot_turn = self.spin_from_side(self.turn).turn(dir);
+        }
+        sounds.push(Sfx::Throw);
+    }
+
+    fn human_turn(&self) -> bool {
+        match self.turn {
+            Side::Left => self.bot_left.is_none(),
+            Side::Right => self.bot_right.is_none(),
+        }
+    }
+
+    // the shot clock only runs while a human has the banana in hand
+    fn clock_running(&self) -> bool {
+        self.rules.shot_clock.is_some()
+            && self.human_turn()
+            && !self.shot_in_progress()
+            && !self.is_over()
+    }
+
+    fn update_clock(&mut self, sounds: &mut Vec<Sfx>) {
+        if !self.clock_running() {
+            return;
+        }
+        self.clock = self.clock.saturating_sub(1);
+        if self.clock > 0 {
+            return;
+        }
+        match self.rules.expiry {
+            Expiry::Fire => {
+                // a banana without any power would fall on the thrower
+                self.counter = self.counter.max(SHOT_CLOCK_POWER);
+                self.throw_player(sounds);
+            }
+            Expiry::Forfeit => {
+                self.counting = false;
+                self.counter = 0;
+                self.pass_turn();
+                self.clock = shot_clock(&self.rules);
+            }
+        }
+    }
+
+    pub fn event(&mut self, command: Command, data: &mut SharedData) {
+        if self.is_over() {
+            return;
+        }
         match (self.turn, &mut self.bot_right, &mut self.bot_left) {
-            (Side::Left, _, None) => self.event_player(event),
-            (Side::Right, None, _) => self.event_player(event),
+            (Side::Left, _, None) => self.event_player(command, &mut data.sounds),
+            (Side::Right, None, _) => self.event_player(command, &mut data.sounds),
             _ => (),
         }
     }
 
-    fn update_bot(bot: &mut Bot, center: Vector) -> Option<(Circle, Vector, f32)> {
-        bot.counter = cmp::min(bot.counter + 3, BOT_COUNTER_MAX);
-        if bot.counter == BOT_COUNTER_MAX {
-            let power = bot.counter;
-            bot.counter = 0;
-            Some((
-                Circle::new(center + bot.dir * 4 * SHOT_RADIUS, SHOT_RADIUS),
-                bot.dir * 0.006 * power,
-                0.0,
-            ))
-        } else {
-            None
+    fn reset_bots(&mut self) {
+        for bot in self.bot_left.iter_mut().chain(self.bot_right.iter_mut()) {
+            bot.new_round();
         }
     }
 
-    fn reset_bots(&mut self) {
-        if self.bot_right.is_some() {
-            self.bot_right = Some(Bot::new(Side::Right, &self.round));
+    // rebuilds the undamaged terrain of the current round, a decided round is taken back
+    pub fn restart_round(&mut self, data: &mut SharedData) {
+        for (pool, building) in data.parts.iter_mut().zip(self.round.buildings.iter()) {
+            pool.clear();
+            pool.push(building.bound_box);
+        }
+        match self.new_game.take() {
+            Some(Side::Left) => self.points_left -= 1,
+            Some(Side::Right) => self.points_right -= 1,
+            None => (),
+        }
+        match self.round.penalty.take() {
+            Some(Side::Left) => self.points_left += 1,
+            Some(Side::Right) => self.points_right += 1,
+            None => (),
+        }
+        data.particle_buffer.clear();
+        self.explosion_masks.clear();
+        self.round.debris.clear();
+        self.round.objects = self.round.spawned_objects.clone();
+        self.explosion_state = None;
+        self.shot = None;
+        self.counting = false;
+        self.counter = 0;
+        self.round.shots_left = 0;
+        self.round.shots_right = 0;
+        self.round.frames = 0;
+        self.round.turns = 0;
+        self.round.flood = WINDOW_Y;
+        // the range starts over with new targets
+        if let Some(settings) = self.practice.as_ref().map(|x| x.settings) {
+            let boxes = self.building_boxes();
+            let thrower = self.round.gorilla_left;
+            self.practice = Some(Range::new(settings, &boxes, &thrower, &mut self.rng));
         }
-        if self.bot_left.is_some() {
-            self.bot_left = Some(Bot::new(Side::Left, &self.round));
+        self.round.landings_left.clear();
+        self.round.landings_right.clear();
+        // a gorilla that came down with the rubble goes back up on its roof
+        for side in [Side::Left, Side::Right].iter().copied() {
+            let x = self.gorilla_from_side(side).center().x;
+            let roof = self
+                .round
+                .buildings
+                .iter()
+                .map(|b| b.bound_box)
+                .find(|b| b.pos.x <= x && x <= b.pos.x + b.size.x);
+            if let Some(roof) = roof {
+                self.gorilla_from_side_mut(side).pos.y = roof.pos.y - GORILLA_SIZE.1 as f32;
+            }
         }
+        self.round.render_score = true;
+        self.clock = shot_clock(&self.rules);
+        self.reset_bots();
     }
 
-    fn update_aim(&mut self, pos: Vector) {
-        match (self.turn, self.bot_right.as_mut(), self.bot_left.as_mut()) {
-            (Side::Left, _, Some(bot)) => bot.aim(pos),
-            (Side::Right, Some(bot), _) => bot.aim(pos),
-            _ => (),
+    // where a missed shot ended, for the bots to correct their aim
+    fn record_landing(&mut self, pos: Vector) {
+        match self.turn {
+            Side::Left => self.round.landings_left.push(pos),
+            Side::Right => self.round.landings_right.push(pos),
+        }
+    }
+
+    // the bot that threw may have something to say about how it went
+    fn say(&mut self, hit: bool) {
+        let bot = match self.turn {
+            Side::Left => self.bot_left.as_ref(),
+            Side::Right => self.bot_right.as_ref(),
         };
+        if let Some(text) = bot.and_then(|x| x.taunt(hit)) {
+            self.taunt = Some(Taunt {
+                side: self.turn,
+                text,
+                frames: (TAUNT_MS / DELTAT_MS) as u32,
+                image: None,
+            });
+        }
     }
 
     fn shot_in_progress(&self) -> bool {
         self.shot.is_some() || self.explosion_state.is_some()
     }
 
+    fn update_aim_input(&mut self) {
+        let rate = self.aim_input.rate();
+        if rate != 0.0 && !self.shot_in_progress() {
+            // aiming up raises the aim, whichever way the gorilla is facing
+            let up = match self.turn {
+                Side::Left => -AIM_ROTATION_SPEED,
+                Side::Right => AIM_ROTATION_SPEED,
+            };
+            let aim = self.aim_from_side_mut(self.turn);
+            *aim = Vector::from_angle(aim.angle() + up * rate);
+        }
+    }
+
+    // every throw ends a turn, the next one starts with a full shot clock
+    fn count_shot(&mut self, weapon: Weapon) {
+        self.clock = shot_clock(&self.rules);
+        self.shot_weapon = weapon;
+        self.bounces = 0;
+        self.shot_turn = 0.0;
+        match self.turn {
+            Side::Left => self.round.shots_left += 1,
+            Side::Right => self.round.shots_right += 1,
+        }
+        if let Some(range) = self.practice.as_mut() {
+            range.throw();
+            self.round.render_score = true;
+        }
+    }
+
     pub fn update(&mut self, data: &mut SharedData, window: &mut Window) -> Result<()> {
+        self.step(data);
+
+        if let Some(juice) = self.juice.as_mut() {
+            *juice += JUICE_COUNTER;
+            let y = 10.0 * (-2.0 * *juice).exp2() * juice.sin();
+            let x = 0.2 * (-2.0 * *juice).exp2() * juice.sin();
+            let view = if y.abs() < 0.01 {
+                self.juice = None;
+                Rectangle::new_sized((800, 600))
+            } else {
+                Rectangle::new((x, y), (800, 600))
+            };
+            window.set_view(View::new(view));
+        }
+        Ok(())
+    }
+
+    // advances the match by one tick, without touching the window so it also runs headless
+    pub fn step(&mut self, data: &mut SharedData) {
+        if !self.is_over() {
+            self.round.frames += 1;
+        }
+        if let Some(taunt) = self.taunt.as_mut() {
+            taunt.frames = taunt.frames.saturating_sub(1);
+            if taunt.frames == 0 {
+                self.taunt = None;
+            }
+        }
+        if let Some(notice) = self.notice.as_mut() {
+            notice.frames = notice.frames.saturating_sub(1);
+            if notice.frames == 0 {
+                self.notice = None;
+            }
+        }
         let gorilla = self.gorilla_from_side(self.turn);
         let center = gorilla.pos + (gorilla.size / 2);
 
-        if self.shot.is_none() && self.explosion_state.is_none() {
-            let bot_shot = match (self.turn, &mut self.bot_right, &mut self.bot_left) {
-                (Side::Left, _, Some(bot)) => Game::update_bot(bot, center),
-                (Side::Right, Some(bot), _) => Game::update_bot(bot, center),
-                _ => None,
+        if self.shot.is_none() && self.explosion_state.is_none() && !self.is_over() {
+            let boxes = self.building_boxes();
+            let world = world_view(
+                &self.round,
+                self.turn,
+                &data.parts,
+                Some(boxes.as_slice()).filter(|_| self.rules.wind_field),
+                self.rules.gravity_push(),
+                self.ballistics.drag,
+            );
+            let bot = match self.turn {
+                Side::Left => self.bot_left.as_mut(),
+                Side::Right => self.bot_right.as_mut(),
             };
-            if let Some(shot) = bot_shot {
-                self.shot = Some(shot);
+            let bot_shot = match bot {
+                Some(bot) => bot.update(&world, &mut self.rng),
+                None => None,
+            };
+            if let Some(Shot { dir, power }) = bot_shot {
+                self.shot = Some((
+                    Circle::new(center + dir * 4 * SHOT_RADIUS, SHOT_RADIUS),
+                    dir * SHOT_SPEED_RATIO * power,
+                    0.0,
+                ));
+                self.count_shot(Weapon::Banana);
+                data.sounds.push(Sfx::Throw);
             }
         }
 
         if let Some(state) = self.explosion_state.as_mut() {
             for particle in data.particle_buffer.iter_mut() {
-                let (pos, speed) = update_shot(particle.0, particle.1);
+                let (pos, speed) = update_shot(particle.0, particle.1, self.rules.gravity_push());
                 particle.0 = pos;
                 particle.1 = speed;
             }
 
-            if state.frame / 2 == EXPLOSION_FRAMES {
+            // the turn only passes once the debris has come down, unless the round is over
+            let settled = self.round.debris.is_empty() || self.new_game.is_some();
+            if state.frame / 2 >= EXPLOSION_FRAMES && settled {
                 data.particle_buffer.clear();
                 self.explosion_state = None;
-                self.turn = next_side(self.turn);
+                self.pass_turn();
+                if self.winner().is_some() {
+                    // the field stays as it is until the players leave
+                    data.sounds.push(Sfx::Victory);
+                    return;
+                }
                 if self.new_game.is_some() {
+                    data.sounds.push(Sfx::Victory);
                     for part in data.parts.iter_mut() {
                         part.clear()
                     }
-                    self.round = Round::new(&mut data.parts);
+                    self.round = Round::new(&mut data.parts, &self.rules, &mut self.rng);
                     self.explosion_masks.clear();
                     self.reset_bots();
                     self.new_game = None;
-                    return Ok(());
+                    return;
                 }
-            } else {
+            } else if state.frame / 2 < EXPLOSION_FRAMES {
                 state.frame += 1;
             }
         }
 
+        self.update_debris(data);
+        self.update_flood(data);
+
+        // the wind at a spot, the same everywhere without the wind field
+        let boxes = self.building_boxes();
+        let (round_wind, field) = (self.round.wind, self.rules.wind_field);
+        let gravity = self.rules.gravity_push();
+        let blow = |pos: Vector| match field {
+            true => wind::at(pos, round_wind, &boxes),
+            false => round_wind,
+        };
+
         if let Some((mut circle, prev_speed, mut angle)) = self.shot {
+            let from = circle.pos;
+            let wind = blow(circle.pos) * WIND_PLAY_RATIO;
             let (pos, speed) =
-                update_shot_windy(circle.pos, prev_speed, self.round.wind * WIND_PLAY_RATIO);
+                self.ballistics
+                    .step(circle.pos, prev_speed, wind, gravity, self.shot_turn);
             circle.pos = pos;
-            angle += BANANA_ANG_SPEED;
+            // a spun banana turns the way it spins
+            angle += match self.shot_turn {
+                turn if turn != 0.0 => BANANA_ANG_SPEED * turn,
+                _ => BANANA_ANG_SPEED,
+            };
+            if prev_speed.y < 0.0 && speed.y >= 0.0 {
+                // the banana starts falling
+                data.sounds.push(Sfx::Whoosh);
+            }
             match self.collision(circle, &data.parts) {
                 Collision::None => self.shot = Some((circle, speed, angle)),
+                Collision::Player(side, _) if self.spares(side) => {
+                    self.shot = Some((circle, speed, angle));
+                    self.notify(FRIENDLY_FIRE_OFF.to_string());
+                }
                 Collision::Player(side, xs) => {
-                    match side {
-                        Side::Left => self.points_right += 1,
-                        Side::Right => self.points_left += 1,
+                    self.destroy_terrain(&circle, xs, &mut data.parts);
+                    self.hit_gorilla(side, pos, data);
+                }
+                Collision::Object(i, xs) => {
+                    data.sounds.push(Sfx::BuildingHit);
+                    self.record_landing(pos);
+                    self.say(false);
+                    if self.round.objects[i].destructible() {
+                        self.round.objects.remove(i);
                     }
                     self.destroy_terrain(&circle, xs, &mut data.parts);
-                    let gorilla = self.gorilla_from_side(side);
-                    let gorilla = gorilla.pos + gorilla.size / 2;
-                    self.on_explode(data, pos, Some(gorilla));
-                    self.new_game = Some(next_side(side));
-                    self.round.render_score = true;
+                    self.on_explode(data, pos, None);
+                }
+                Collision::Buildings(_) if self.bounces_off(circle, from, speed, &data.parts) => {
+                    data.sounds.push(Sfx::Whoosh);
                 }
                 Collision::Buildings(xs) => {
-                    self.update_aim(pos);
+                    data.sounds.push(Sfx::BuildingHit);
+                    self.record_landing(pos);
+                    self.say(false);
                     self.destroy_terrain(&circle, xs, &mut data.parts);
                     self.on_explode(data, pos, None);
                 }
+                Collision::Target(i) => {
+                    data.sounds.push(Sfx::GorillaHit);
+                    if let Some(range) = self.practice.as_mut() {
+                        range.take(i);
+                    }
+                    self.round.render_score = true;
+                    self.on_explode(data, pos, Some(pos));
+                }
+                Collision::Flood => {
+                    self.record_landing(pos);
+                    self.say(false);
+                    self.on_explode(data, pos, None);
+                }
                 _ => {
-                    self.update_aim(pos);
-                    self.turn = next_side(self.turn);
+                    self.record_landing(pos);
+                    self.say(false);
+                    self.pass_turn();
                     self.shot = None;
                 }
             }
         }
 
+        for object in self.round.objects.iter_mut() {
+            object.update();
+        }
+        if let Some(range) = self.practice.as_mut() {
+            range.update();
+            if let Some((circle, _, _)) = self.shot {
+                range.trace(circle.pos);
+            }
+        }
+
         for drop in self.round.rain.iter_mut() {
-            *drop += (self.round.wind + Vector::new(0.0, GRAVITY)) * WIND_SHOW_RATIO;
+            *drop += (blow(*drop) + Vector::new(0.0, gravity)) * WIND_SHOW_RATIO;
             let mut rng = rand::thread_rng();
             if drop.y > WINDOW_Y {
                 drop.x = rng.gen_range(0.0, 1.0) * WINDOW_X;
@@ -762,18 +2197,8 @@ impl Game {
             }
         }
 
-        if let Some(juice) = self.juice.as_mut() {
-            *juice += JUICE_COUNTER;
-            let y = 10.0 * (-2.0 * *juice).exp2() * juice.sin();
-            let x = 0.2 * (-2.0 * *juice).exp2() * juice.sin();
-            let view = if y.abs() < 0.01 {
-                self.juice = None;
-                Rectangle::new_sized((800, 600))
-            } else {
-                Rectangle::new((x, y), (800, 600))
-            };
-            window.set_view(View::new(view));
-        }
+        self.update_aim_input();
+        self.update_clock(&mut data.sounds);
 
         if self.counting {
             self.counter = cmp::min(self.counter + 3, POWER_MAX);
@@ -783,6 +2208,5 @@ impl Game {
                 self.counter = 0;
             }
         }
-        Ok(())
     }
 }
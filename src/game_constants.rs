@@ -2,7 +2,6 @@ use quicksilver::geom::Vector;
 
 pub const WINDOW_X: f32 = 800.0;
 pub const WINDOW_Y: f32 = 600.0;
-pub const CENTER: (f32, f32) = (WINDOW_X / 2.0, WINDOW_Y / 2.0);
 pub const GRAVITY: f32 = 0.012;
 pub const DELTAT_MS: f32 = 16.667;
 pub const GORILLA_SIZE: (u32, u32) = (64, 96);
@@ -12,15 +11,51 @@ pub const EXPLOSION_SIZE: (u32, u32) = (96, 96);
 pub const EXPLOSION_HALF_VEC: Vector = Vector { x: 48.0, y: 48.0 };
 pub const EXPLOSION_DESTROY_SCALE: f32 = 4.0;
 pub const POWER_MAX: i32 = 200;
+pub const SHOT_SPEED_RATIO: f32 = 0.006;
 pub const JUICE_COUNTER: f32 = 0.5;
 pub const START_OFFSET: f32 = 60.0;
 pub const END_OFFSET: f32 = 80.0;
 pub const DISTANCE_MIN: usize = 2;
+pub const BUILDING_POOLS: usize = 11;
 pub const PARTS_GRID_MIN: f32 = 4.0;
 pub const TITLE: &str = "gorillas.rs";
 pub const PLAY: &str = "Play [Enter]";
+pub const SETTINGS: &str = "Settings";
+pub const CONTINUE: &str = "Continue";
+pub const LOAD: &str = "Load saved match";
+pub const STATISTICS: &str = "Statistics";
+pub const TOURNAMENT: &str = "Tournament";
+pub const RULES: &str = "Rules";
+pub const OVERLAY_ALPHA: f32 = 0.6;
+pub const MENU_TITLE_Y: f32 = 70.0;
+pub const MENU_TOP: f32 = 150.0;
+pub const CONTROLS_TOP: f32 = 115.0;
+pub const MENU_LINE_HEIGHT: f32 = 32.0;
 pub const BOT_COUNTER_MAX: i32 = 200;
 pub const BOT_AIM_INC_Y: f32 = 0.1;
+pub const BOT_EASY_AIM_NOISE: f32 = 8.0; // degrees
+pub const BOT_HARD_MISS_DISTANCE: f32 = 200.0;
+pub const BOT_ELEVATIONS: [f32; 7] = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0]; // degrees
+pub const BOT_PATH_STEPS: usize = 2000;
+pub const BOT_WIND_MAX: f32 = 0.02;
+pub const BOT_WIND_STEPS: usize = 24;
+pub const EXTERNAL_TIMEOUT_MS: u64 = 2000;
+pub const EXTERNAL_STRIKES: u32 = 3;
+pub const AIM_ROTATION_SPEED: f32 = 1.5; // degrees per update
+pub const TAUNT_MS: f32 = 2000.0;
+pub const TAUNT_OFFSET: f32 = 24.0;
+pub const SHOT_CLOCK_CHOICES: [u32; 3] = [10, 20, 30]; // seconds
+pub const SHOT_CLOCK_Y: f32 = 150.0;
+pub const NOTICE_MS: f32 = 2500.0;
+pub const NOTICE_Y: f32 = 180.0;
+pub const FRIENDLY_FIRE_OFF: &str = "Friendly fire is off";
+// the banana thrown for a human who let the shot clock run out without charging
+pub const SHOT_CLOCK_POWER: i32 = POWER_MAX / 2;
+pub const SHOT_CLOCK_WARNING: u32 = 3; // seconds
+
+// the gorilla sprite sheets a profile can pick from, by name
+pub const SKINS: [(&str, &str); 2] = [("bowtie", "Bowtie.png"), ("plain", "Player.png")];
+pub const SCORE_NAME_GAP: f32 = 100.0;
 
 pub const TILE_SIZE: (u32, u32) = (16, 16);
 
@@ -41,3 +76,104 @@ pub const WIND_PLAY_RATIO: f32 = 0.003;
 pub const BANANA_SIZE: (u32, u32) = (20, 20);
 pub const BANANA_LOC: (u32, u32) = (0, 128);
 pub const BANANA_ANG_SPEED: f32 = 5.0;
+
+pub const MUSIC_LENGTH_MS: f32 = 6400.0;
+pub const VOLUME_STEP: f32 = 0.1;
+
+pub const TOURNAMENT_MIN_ENTRANTS: usize = 4;
+pub const TOURNAMENT_MAX_ENTRANTS: usize = 16;
+pub const TOURNAMENT_MATCH_LENGTH: u32 = 2;
+pub const TOURNAMENT_RESULT_MS: f32 = 3000.0;
+pub const TOURNAMENT_LINE_HEIGHT: f32 = 26.0;
+pub const TOURNAMENT_COLUMN_LINES: usize = 8;
+
+pub const PLATFORM_SIZE: (f32, f32) = (40.0, 8.0);
+pub const PLATFORM_SPEED: f32 = 0.04; // pixels per millisecond
+pub const PLATFORM_COLOR: &str = "8b9bb4";
+pub const BILLBOARD_SIZE: (f32, f32) = (56.0, 28.0);
+pub const BILLBOARD_CHANCE: f64 = 0.3;
+pub const BILLBOARD_COLOR: &str = "e43b44";
+pub const BIRDS_MAX: u32 = 2;
+pub const BIRD_SIZE: (f32, f32) = (20.0, 10.0);
+pub const BIRD_HEIGHT: (f32, f32) = (60.0, 220.0);
+pub const BIRD_SPEED: f32 = 0.08;
+pub const BIRD_COLOR: &str = "262b44";
+pub const BLIMP_CHANCE: f64 = 0.5;
+pub const BLIMP_SIZE: (f32, f32) = (120.0, 36.0);
+pub const BLIMP_HEIGHT: (f32, f32) = (30.0, 120.0);
+pub const BLIMP_SPEED: f32 = 0.02;
+pub const BLIMP_COLOR: &str = "c0cbdc";
+
+// a banana glancing off a wall at less than this bounces instead of going off
+pub const RICOCHET_MAX_ANGLE: f32 = 35.0; // degrees
+pub const RICOCHET_RESTITUTION: f32 = 0.6;
+pub const RICOCHET_FRICTION: f32 = 0.2;
+pub const RICOCHET_BOUNCE_CHOICES: [u32; 5] = [1, 2, 3, 4, 5];
+pub const BOUNCY_TINT: &str = "63c74d";
+pub const WEAPON_Y: f32 = 480.0;
+
+// the settings for the air, from off to strong
+pub const BALLISTICS_LEVELS: [&str; 3] = ["off", "light", "strong"];
+pub const DRAG_CHOICES: [f32; 3] = [0.0, 0.003, 0.006];
+pub const MAGNUS_CHOICES: [f32; 3] = [0.0, 0.002, 0.004];
+// the speed of the air for the push the wind gives without drag
+pub const WIND_AIR_RATIO: f32 = 170.0;
+
+// the wind field, by the bottom of each altitude layer and how much of the wind blows there
+pub const WIND_LAYERS: [(f32, f32); 3] = [(200.0, 1.4), (400.0, 1.0), (WINDOW_Y, 0.6)];
+pub const WIND_SHADOW_LENGTH: f32 = 2.0;
+pub const WIND_SHADOW_MIN: f32 = 0.2;
+pub const WIND_GRID: f32 = 80.0;
+pub const WIND_GRID_ALPHA: f32 = 0.3;
+
+// gravity in m/s², the original game's prompt took the same values
+pub const GRAVITY_PRESETS: [(&str, f32); 4] = [
+    ("Moon", 1.6),
+    ("Mars", 3.7),
+    ("Earth", 9.8),
+    ("Jupiter", 24.8),
+];
+// the gravity that makes a banana fall by GRAVITY every update
+pub const EARTH_GRAVITY: f32 = 9.8;
+pub const GRAVITY_MAX: f32 = 100.0;
+// a custom gravity is set in steps of this on the rules screen
+pub const GRAVITY_STEP: f32 = 0.1;
+
+// a crater is a big hole with a few smaller ones around its edge
+pub const CRATER_JITTER: (f32, f32) = (0.8, 1.1);
+pub const CRATER_LOBES: (u32, u32) = (2, 5);
+pub const CRATER_LOBE_RADIUS: (f32, f32) = (0.35, 0.65);
+pub const DEBRIS_TOUCH: f32 = 0.01;
+pub const DEBRIS_CRATER_MIN: f32 = 6.0;
+// a floor narrower than this part of the widest floor above it gives way
+pub const COLLAPSE_SUPPORT: f32 = 0.25;
+pub const RUBBLE_MIN_AREA: f32 = 400.0;
+pub const RIDER_TOLERANCE: f32 = 1.0;
+// the masks over the round surface are drawn between z 2.0 and 2.0 plus this
+pub const MASK_Z_RANGE: f32 = 0.4;
+// turns
+pub const SUDDEN_DEATH_CHOICES: [u32; 3] = [10, 20, 40];
+// how much the flood rises every turn and how fast it gets there, in pixels and per ms
+pub const FLOOD_STEP: f32 = 24.0;
+pub const FLOOD_SPEED: f32 = 0.02;
+// how deep a gorilla can stand in the flood
+pub const FLOOD_REACH: f32 = 4.0;
+pub const FLOOD_ALPHA: f32 = 0.85;
+pub const FLOOD_WATER: &str = "2a6fb0";
+pub const FLOOD_WATER_EDGE: &str = "9fd3ff";
+pub const FLOOD_LAVA: &str = "e0521b";
+pub const FLOOD_LAVA_EDGE: &str = "ffd04a";
+pub const PRACTICE: &str = "Practice";
+pub const PRACTICE_TARGETS: usize = 5;
+pub const PRACTICE_TARGET_RADIUS: f32 = 12.0;
+pub const PRACTICE_TARGET_COLOR: &str = "d63c3c";
+// targets stay this far away from the thrower and this high over the roofs
+pub const PRACTICE_DISTANCE_MIN: f32 = 160.0;
+pub const PRACTICE_HEIGHT: (f32, f32) = (60.0, 260.0);
+pub const PRACTICE_TOP: f32 = 140.0;
+// how far a moving target sways and how fast, in radians per ms
+pub const PRACTICE_SWAY: f32 = 40.0;
+pub const PRACTICE_SWAY_SPEED: f32 = 0.0015;
+pub const PRACTICE_TRAILS: usize = 5;
+pub const PRACTICE_TRAIL_GAP: f32 = 8.0;
+pub const PRACTICE_TRAIL_ALPHA: f32 = 0.4;
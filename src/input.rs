@@ -0,0 +1,355 @@
+use quicksilver::{
+    geom::Vector,
+    input::{ButtonState, GamepadAxis, GamepadButton, Key, MouseButton},
+    lifecycle::Event,
+};
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+pub const STICK_DEADZONE: f32 = 0.2;
+
+// quicksilver's input enums are not serializable, bindings are stored by their debug name
+#[rustfmt::skip]
+const KEYS: &[Key] = &[
+    Key::Key1, Key::Key2, Key::Key3, Key::Key4, Key::Key5, Key::Key6, Key::Key7, Key::Key8,
+    Key::Key9, Key::Key0, Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I,
+    Key::J, Key::K, Key::L, Key::M, Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T, Key::U,
+    Key::V, Key::W, Key::X, Key::Y, Key::Z, Key::Escape, Key::F1, Key::F2, Key::F3, Key::F4,
+    Key::F5, Key::F6, Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12, Key::Insert,
+    Key::Home, Key::Delete, Key::End, Key::PageDown, Key::PageUp, Key::Left, Key::Up, Key::Right,
+    Key::Down, Key::Back, Key::Return, Key::Space, Key::Numpad0, Key::Numpad1, Key::Numpad2,
+    Key::Numpad3, Key::Numpad4, Key::Numpad5, Key::Numpad6, Key::Numpad7, Key::Numpad8,
+    Key::Numpad9, Key::Add, Key::Subtract, Key::Multiply, Key::Divide, Key::Decimal,
+    Key::NumpadEnter, Key::Apostrophe, Key::Backslash, Key::Comma, Key::Equals, Key::Grave,
+    Key::LBracket, Key::RBracket, Key::Minus, Key::Period, Key::Semicolon, Key::Slash, Key::Tab,
+    Key::LAlt, Key::RAlt, Key::LControl, Key::RControl, Key::LShift, Key::RShift,
+];
+
+const MOUSE_BUTTONS: &[MouseButton] = &[MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+const PAD_BUTTONS: &[GamepadButton] = &[
+    GamepadButton::FaceDown,
+    GamepadButton::FaceRight,
+    GamepadButton::FaceLeft,
+    GamepadButton::FaceUp,
+    GamepadButton::ShoulderLeft,
+    GamepadButton::ShoulderRight,
+    GamepadButton::TriggerLeft,
+    GamepadButton::TriggerRight,
+    GamepadButton::Select,
+    GamepadButton::Start,
+    GamepadButton::StickButtonLeft,
+    GamepadButton::StickButtonRight,
+    GamepadButton::DpadUp,
+    GamepadButton::DpadDown,
+    GamepadButton::DpadLeft,
+    GamepadButton::DpadRight,
+    GamepadButton::Home,
+];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Confirm,
+    Back,
+    Previous,
+    Next,
+    Pause,
+    Fire,
+    AimUp,
+    AimDown,
+    Weapon,
+    Spin,
+    Mute,
+    VolumeUp,
+    VolumeDown,
+}
+
+pub const ACTIONS: [Action; 13] = [
+    Action::Confirm,
+    Action::Back,
+    Action::Previous,
+    Action::Next,
+    Action::Pause,
+    Action::Fire,
+    Action::AimUp,
+    Action::AimDown,
+    Action::Weapon,
+    Action::Spin,
+    Action::Mute,
+    Action::VolumeUp,
+    Action::VolumeDown,
+];
+
+// the same input can be bound in several contexts, each screen only looks up its own actions
+pub const GLOBAL_ACTIONS: [Action; 3] = [Action::Mute, Action::VolumeUp, Action::VolumeDown];
+pub const MENU_ACTIONS: [Action; 4] = [
+    Action::Confirm,
+    Action::Back,
+    Action::Previous,
+    Action::Next,
+];
+pub const PAUSE_ACTIONS: [Action; 5] = [
+    Action::Pause,
+    Action::Confirm,
+    Action::Back,
+    Action::Previous,
+    Action::Next,
+];
+pub const GAME_ACTIONS: [Action; 7] = [
+    Action::Pause,
+    Action::Back,
+    Action::Fire,
+    Action::AimUp,
+    Action::AimDown,
+    Action::Weapon,
+    Action::Spin,
+];
+
+// the global actions are asked on every screen along with one of the others
+fn share_context(a: Action, b: Action) -> bool {
+    let contexts: [&[Action]; 3] = [&MENU_ACTIONS, &PAUSE_ACTIONS, &GAME_ACTIONS];
+    GLOBAL_ACTIONS.contains(&a)
+        || GLOBAL_ACTIONS.contains(&b)
+        || contexts.iter().any(|x| x.contains(&a) && x.contains(&b))
+}
+
+// the devices a player's turn listens to, so two players can share one computer
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputMode {
+    Any,
+    Keyboard,
+    Mouse,
+    Gamepad,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Input {
+    Key(Key),
+    Mouse(MouseButton),
+    Pad(GamepadButton),
+}
+
+// what the screens consume instead of raw events
+#[derive(Copy, Clone, Debug)]
+pub enum Command {
+    Action(Action, ButtonState),
+    PointerMoved(Vector),
+    PointerClicked,
+    AimAxis(f32),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(
+    from = "BTreeMap<Action, Vec<Input>>",
+    into = "BTreeMap<Action, Vec<Input>>"
+)]
+pub struct Bindings {
+    map: BTreeMap<Action, Vec<Input>>,
+}
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Confirm => "Confirm",
+            Action::Back => "Back",
+            Action::Previous => "Previous",
+            Action::Next => "Next",
+            Action::Pause => "Pause",
+            Action::Fire => "Fire",
+            Action::AimUp => "Aim up",
+            Action::AimDown => "Aim down",
+            Action::Weapon => "Switch weapon",
+            Action::Spin => "Switch spin",
+            Action::Mute => "Mute",
+            Action::VolumeUp => "Volume up",
+            Action::VolumeDown => "Volume down",
+        }
+    }
+}
+
+impl InputMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            InputMode::Any => "any input",
+            InputMode::Keyboard => "keyboard",
+            InputMode::Mouse => "mouse",
+            InputMode::Gamepad => "gamepad",
+        }
+    }
+
+    pub fn accepts(self, event: &Event) -> bool {
+        matches!(
+            (self, event),
+            (InputMode::Any, _)
+                | (InputMode::Keyboard, Event::Key(_, _))
+                | (InputMode::Mouse, Event::MouseMoved(_))
+                | (InputMode::Mouse, Event::MouseButton(_, _))
+                | (InputMode::Gamepad, Event::GamepadButton(_, _, _))
+                | (InputMode::Gamepad, Event::GamepadAxis(_, _, _))
+        )
+    }
+}
+
+impl From<Input> for String {
+    fn from(input: Input) -> String {
+        match input {
+            Input::Key(key) => format!("Key {:?}", key),
+            Input::Mouse(button) => format!("Mouse {:?}", button),
+            Input::Pad(button) => format!("Pad {:?}", button),
+        }
+    }
+}
+
+fn find<T: Copy + std::fmt::Debug>(list: &[T], name: &str) -> Option<T> {
+    list.iter().cloned().find(|x| format!("{:?}", x) == name)
+}
+
+impl TryFrom<String> for Input {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let mut words = s.splitn(2, ' ');
+        let input = match (words.next(), words.next()) {
+            (Some("Key"), Some(name)) => find(KEYS, name).map(Input::Key),
+            (Some("Mouse"), Some(name)) => find(MOUSE_BUTTONS, name).map(Input::Mouse),
+            (Some("Pad"), Some(name)) => find(PAD_BUTTONS, name).map(Input::Pad),
+            _ => None,
+        };
+        input.ok_or_else(|| format!("unknown input binding: {}", s))
+    }
+}
+
+impl Input {
+    pub fn from_event(event: &Event) -> Option<(Input, ButtonState)> {
+        match *event {
+            Event::Key(key, state) => Some((Input::Key(key), state)),
+            Event::MouseButton(button, state) => Some((Input::Mouse(button), state)),
+            Event::GamepadButton(_, button, state) => Some((Input::Pad(button), state)),
+            _ => None,
+        }
+    }
+
+    fn same_device(self, other: Input) -> bool {
+        matches!(
+            (self, other),
+            (Input::Key(_), Input::Key(_))
+                | (Input::Mouse(_), Input::Mouse(_))
+                | (Input::Pad(_), Input::Pad(_))
+        )
+    }
+}
+
+fn default_inputs(action: Action) -> Vec<Input> {
+    use GamepadButton as Pad;
+    match action {
+        Action::Confirm => vec![Input::Key(Key::Return), Input::Pad(Pad::FaceDown)],
+        Action::Back => vec![Input::Key(Key::Escape), Input::Pad(Pad::FaceRight)],
+        Action::Previous => vec![
+            Input::Key(Key::Up),
+            Input::Key(Key::Left),
+            Input::Pad(Pad::DpadUp),
+            Input::Pad(Pad::DpadLeft),
+        ],
+        Action::Next => vec![
+            Input::Key(Key::Down),
+            Input::Key(Key::Right),
+            Input::Key(Key::Tab),
+            Input::Pad(Pad::DpadDown),
+            Input::Pad(Pad::DpadRight),
+        ],
+        Action::Pause => vec![Input::Key(Key::Space), Input::Pad(Pad::Start)],
+        Action::Fire => vec![
+            Input::Mouse(MouseButton::Left),
+            Input::Key(Key::Return),
+            Input::Pad(Pad::FaceDown),
+        ],
+        Action::AimUp => vec![
+            Input::Key(Key::Up),
+            Input::Key(Key::W),
+            Input::Pad(Pad::DpadUp),
+        ],
+        Action::AimDown => vec![
+            Input::Key(Key::Down),
+            Input::Key(Key::S),
+            Input::Pad(Pad::DpadDown),
+        ],
+        Action::Weapon => vec![Input::Key(Key::Q), Input::Pad(Pad::FaceLeft)],
+        Action::Spin => vec![Input::Key(Key::E), Input::Pad(Pad::FaceUp)],
+        Action::Mute => vec![Input::Key(Key::M)],
+        Action::VolumeUp => vec![Input::Key(Key::Equals), Input::Key(Key::Add)],
+        Action::VolumeDown => vec![Input::Key(Key::Minus), Input::Key(Key::Subtract)],
+    }
+}
+
+// actions missing from an older config file get their default inputs
+impl From<BTreeMap<Action, Vec<Input>>> for Bindings {
+    fn from(mut map: BTreeMap<Action, Vec<Input>>) -> Self {
+        for action in ACTIONS.iter() {
+            map.entry(*action)
+                .or_insert_with(|| default_inputs(*action));
+        }
+        Bindings { map }
+    }
+}
+
+impl From<Bindings> for BTreeMap<Action, Vec<Input>> {
+    fn from(bindings: Bindings) -> Self {
+        bindings.map
+    }
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Bindings::from(BTreeMap::new())
+    }
+}
+
+impl Bindings {
+    pub fn inputs(&self, action: Action) -> &[Input] {
+        self.map.get(&action).map(|x| x.as_slice()).unwrap_or(&[])
+    }
+
+    // replaces the inputs of the same device, so rebinding a key keeps the gamepad button.
+    // The input is taken from the actions asked together with this one, command would only
+    // ever find the first of them
+    pub fn rebind(&mut self, action: Action, input: Input) {
+        for (other, inputs) in self.map.iter_mut() {
+            if *other != action && share_context(action, *other) {
+                inputs.retain(|x| *x != input);
+            }
+        }
+        let inputs = self.map.entry(action).or_default();
+        inputs.retain(|x| !x.same_device(input));
+        inputs.push(input);
+    }
+
+    pub fn command(&self, event: &Event, context: &[Action]) -> Option<Command> {
+        match *event {
+            Event::MouseMoved(pos) => return Some(Command::PointerMoved(pos)),
+            Event::GamepadAxis(_, GamepadAxis::LeftStickY, value) => {
+                return if value.abs() < STICK_DEADZONE {
+                    Some(Command::AimAxis(0.0))
+                } else {
+                    Some(Command::AimAxis(value))
+                };
+            }
+            _ => (),
+        }
+        let (input, state) = Input::from_event(event)?;
+        let action = context
+            .iter()
+            .find(|action| self.inputs(**action).contains(&input));
+        match (action, input, state) {
+            (Some(action), _, _) => Some(Command::Action(*action, state)),
+            (None, Input::Mouse(MouseButton::Left), ButtonState::Pressed) => {
+                Some(Command::PointerClicked)
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,129 @@
+pub mod audio;
+pub mod ballistics;
+pub mod bracket;
+pub mod cli;
+pub mod config;
+pub mod debris;
+pub mod external;
+pub mod game;
+pub mod game_constants;
+pub mod input;
+pub mod menu;
+pub mod named;
+pub mod objects;
+pub mod personality;
+pub mod practice;
+pub mod profile;
+pub mod rules;
+pub mod save;
+pub mod stats;
+pub mod strategy;
+pub mod tournament;
+pub mod wind;
+
+use crate::audio::Sfx;
+use crate::ballistics::Ballistics;
+use crate::game::Player;
+use crate::game::RoundSummary;
+use crate::game_constants::*;
+use crate::personality::Personality;
+use crate::practice::Practice;
+use crate::profile::Profile;
+use crate::rules::Rules;
+
+use quicksilver::{
+    geom::{Rectangle, Vector},
+    graphics::{Color, Font, FontStyle, Image},
+    lifecycle::Asset,
+};
+
+use std::cell::RefCell;
+
+pub struct SharedAssets {
+    // one sheet for each of SKINS
+    pub player_tiles: Vec<RefCell<Asset<Image>>>,
+    pub building_tiles: RefCell<Asset<Image>>,
+    pub sky: RefCell<Asset<Image>>,
+    pub explosion: RefCell<Asset<Image>>,
+    pub font: RefCell<Asset<Font>>,
+    pub default_style: FontStyle,
+    pub hoover_style: FontStyle,
+    pub small_style: FontStyle,
+    pub small_hoover_style: FontStyle,
+}
+
+pub struct SharedData {
+    pub particle_buffer: Vec<(Vector, Vector, Color)>,
+    pub parts: Vec<Vec<Rectangle>>,
+    pub sounds: Vec<Sfx>,
+    // finished rounds, drained by whoever keeps the score
+    pub rounds: Vec<RoundSummary>,
+}
+
+#[derive(Clone)]
+pub struct GameConfig {
+    pub left: Player,
+    pub right: Player,
+    pub match_length: Option<u32>,
+    pub seed: Option<u64>,
+    // programs playing a bot side, see external.rs for the protocol
+    pub left_command: Option<String>,
+    pub right_command: Option<String>,
+    // without a window every frame waits for the programs instead of playing on
+    pub headless: bool,
+    pub left_personality: Personality,
+    pub right_personality: Personality,
+    // only human sides play under a profile
+    pub left_profile: Profile,
+    pub right_profile: Profile,
+    pub rules: Rules,
+    pub ballistics: Ballistics,
+    // a target range to throw at instead of a match, the right side sits it out
+    pub practice: Option<Practice>,
+}
+
+impl SharedAssets {
+    pub fn new() -> Self {
+        SharedAssets {
+            explosion: RefCell::new(Asset::new(Image::load("Explosion.png"))),
+            sky: RefCell::new(Asset::new(Image::load("Sky.png"))),
+            building_tiles: RefCell::new(Asset::new(Image::load("Buildings.png"))),
+            player_tiles: SKINS
+                .iter()
+                .map(|x| RefCell::new(Asset::new(Image::load(x.1))))
+                .collect(),
+            font: RefCell::new(Asset::new(Font::load("UI.ttf"))),
+            default_style: FontStyle::new(64.0, Color::WHITE),
+            hoover_style: FontStyle::new(64.0, Color::RED),
+            small_style: FontStyle::new(24.0, Color::WHITE),
+            small_hoover_style: FontStyle::new(24.0, Color::RED),
+        }
+    }
+}
+
+impl Default for SharedAssets {
+    fn default() -> Self {
+        SharedAssets::new()
+    }
+}
+
+impl SharedData {
+    pub fn new() -> Self {
+        let mut parts = vec![];
+        for _ in 0..BUILDING_POOLS {
+            parts.push(Vec::with_capacity(512));
+        }
+        SharedData {
+            particle_buffer: Vec::with_capacity(PARTICLE_COUNT),
+            parts,
+            sounds: vec![],
+            rounds: vec![],
+        }
+    }
+}
+
+impl Default for SharedData {
+    fn default() -> Self {
+        SharedData::new()
+    }
+}
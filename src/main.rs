@@ -1,93 +1,242 @@
-mod game;
-mod game_constants;
-
-use crate::game::*;
-use crate::game_constants::*;
+use gorillas_rs::audio::*;
+use gorillas_rs::cli::{self, Options, USAGE};
+use gorillas_rs::config::*;
+use gorillas_rs::game::*;
+use gorillas_rs::game_constants::*;
+use gorillas_rs::input::*;
+use gorillas_rs::menu::*;
+use gorillas_rs::named;
+use gorillas_rs::personality::Personality;
+use gorillas_rs::profile::Profile;
+use gorillas_rs::save::{self, SAVE_PATH};
+use gorillas_rs::stats::{Stats, STATS_PATH};
+use gorillas_rs::strategy::{StrategyId, STRATEGIES};
+use gorillas_rs::tournament::{Entrant, Format, Tournament, TOURNAMENT_PATH};
+use gorillas_rs::{GameConfig, SharedAssets, SharedData};
 
 use quicksilver::{
     geom::{Rectangle, Shape, Transform, Vector},
-    graphics::{Background::Img, Color, Font, FontStyle, Image},
-    input::{ButtonState, Key, MouseButton},
-    lifecycle::{run, Asset, Event, Settings, State, Window},
+    graphics::{Background::Img, Color, View},
+    input::ButtonState,
+    lifecycle::{run, Event, Settings, State, Window},
     Result,
 };
 
-use std::cell::RefCell;
+use std::sync::OnceLock;
+
+// quicksilver builds the state itself, the command line reaches it through here
+static OPTIONS: OnceLock<Options> = OnceLock::new();
 
-pub struct SharedAssets {
-    player_tiles: RefCell<Asset<Image>>,
-    building_tiles: RefCell<Asset<Image>>,
-    sky: RefCell<Asset<Image>>,
-    explosion: RefCell<Asset<Image>>,
-    font: RefCell<Asset<Font>>,
-    default_style: FontStyle,
-    hoover_style: FontStyle,
+const PAUSE_ITEMS: [&str; 5] = [
+    "Resume",
+    "Restart round",
+    "Save match",
+    "Settings",
+    "Quit to menu",
+];
+
+struct PauseMenu {
+    list: ListMenu,
 }
 
-pub struct SharedData {
-    particle_buffer: Vec<(Vector, Vector, Color)>,
-    parts: Vec<Vec<Rectangle>>,
+enum PauseChoice {
+    Resume,
+    Restart,
+    Save,
+    Settings,
+    Quit,
 }
 
-pub struct GameConfig {
-    bot_left: bool,
-    bot_right: bool,
+// what happens once the player confirms
+enum Confirm {
+    Quit,
+    NewGame(Box<GameConfig>),
+    Load,
+    NextMatch,
 }
 
-struct PauseMenu;
+struct ConfirmMenu {
+    title: &'static str,
+    list: ListMenu,
+    pending: Confirm,
+}
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 enum Hoover {
     None,
+    Continue,
     Left,
+    LeftStrategy,
+    LeftPersonality,
+    LeftProfile,
     Right,
+    RightStrategy,
+    RightPersonality,
+    RightProfile,
     Play,
+    Practice,
+    Tournament,
+    Rules,
+    Settings,
+    Statistics,
+    Load,
+}
+
+const MAIN_MENU_ITEMS: [Hoover; 16] = [
+    Hoover::Continue,
+    Hoover::Left,
+    Hoover::LeftStrategy,
+    Hoover::LeftPersonality,
+    Hoover::LeftProfile,
+    Hoover::Right,
+    Hoover::RightStrategy,
+    Hoover::RightPersonality,
+    Hoover::RightProfile,
+    Hoover::Play,
+    Hoover::Practice,
+    Hoover::Tournament,
+    Hoover::Rules,
+    Hoover::Settings,
+    Hoover::Statistics,
+    Hoover::Load,
+];
+
+enum MenuChoice {
+    Continue,
+    Play(Box<GameConfig>),
+    Practice,
+    Tournament,
+    Rules,
+    Settings,
+    Statistics,
+    Load,
 }
 
 struct MainMenu {
     dirty: bool,
-    bot_right: bool,
-    bot_left: bool,
+    // a match is suspended in memory or saved on disk
+    suspended: bool,
+    saved: bool,
+    message: Option<String>,
+    config: GameConfig,
+    personalities: Vec<Personality>,
+    profiles: Vec<Profile>,
     hoover: Hoover,
     areas: Vec<(Rectangle, Hoover)>,
 }
 
+#[derive(Copy, Clone, PartialEq)]
 enum Focus {
     Main,
     Game,
     Pause,
+    Settings,
+    Controls,
+    Statistics,
+    Tournament,
+    TournamentSetup,
+    Rules,
+    Practice,
+    Confirm,
 }
 
 struct States {
+    config: Config,
+    config_path: String,
+    view_ready: bool,
+    audio: Audio,
     shared_assets: SharedAssets,
     shared_data: SharedData,
     focus: Focus,
     game: Option<Game>,
     pause_menu: PauseMenu,
     main_menu: MainMenu,
+    settings_menu: SettingsMenu,
+    controls_menu: ControlsMenu,
+    stats: Stats,
+    stats_menu: StatsMenu,
+    rules_menu: RulesMenu,
+    practice_menu: PracticeMenu,
+    // the current match is already in the statistics
+    recorded: bool,
+    // the statistics before the decided round was added, a restart of that round puts them back
+    undo_stats: Option<Stats>,
+    tournament: Option<Tournament>,
+    tournament_menu: Option<TournamentMenu>,
+    tournament_setup: Option<TournamentSetup>,
+    // the pairing the current match decides
+    tournament_match: Option<(usize, usize)>,
+    // updates the result of a tournament match stays on screen
+    result_frames: u32,
+    confirm_menu: Option<ConfirmMenu>,
+    // the settings can be opened from the main menu or the pause menu
+    settings_return: Focus,
 }
 
 impl PauseMenu {
+    fn new() -> Self {
+        PauseMenu {
+            list: ListMenu::new(PAUSE_ITEMS.iter().map(|x| x.to_string()).collect()),
+        }
+    }
+
     fn draw(&mut self, shared: &SharedAssets, window: &mut Window) -> Result<()> {
-        shared.font.borrow_mut().execute(|f| {
-            if let Ok(ref text) = f.render("PAUSED", &shared.default_style) {
-                window.draw_ex(
-                    &text.area().with_center(CENTER),
-                    Img(text),
-                    Transform::IDENTITY,
-                    4.0,
-                );
-            } else {
-                eprintln!("Failed to render pause menu")
-            }
-            Ok(())
-        })
+        draw_overlay(window);
+        self.list.draw("PAUSED", shared, window)
+    }
+
+    fn event(&mut self, command: Command, sounds: &mut Vec<Sfx>) -> Option<PauseChoice> {
+        if let Command::Action(Action::Pause, ButtonState::Pressed) = command {
+            return Some(PauseChoice::Resume);
+        }
+        match self.list.event(command, sounds)? {
+            MenuEvent::Selected(1) => Some(PauseChoice::Restart),
+            MenuEvent::Selected(2) => Some(PauseChoice::Save),
+            MenuEvent::Selected(3) => Some(PauseChoice::Settings),
+            MenuEvent::Selected(4) => Some(PauseChoice::Quit),
+            _ => Some(PauseChoice::Resume),
+        }
+    }
+}
+
+impl ConfirmMenu {
+    fn new(pending: Confirm) -> Self {
+        let (title, items): (_, &[&str]) = match pending {
+            Confirm::Quit => (
+                "Quit to menu?",
+                &["Keep the match for later", "Abandon the match", "Cancel"],
+            ),
+            Confirm::NewGame(_) => ("Abandon the match?", &["Start a new match", "Cancel"]),
+            Confirm::Load => ("Abandon the match?", &["Load the saved match", "Cancel"]),
+            Confirm::NextMatch => (
+                "Abandon the match?",
+                &["Play the tournament match", "Cancel"],
+            ),
+        };
+        ConfirmMenu {
+            title,
+            list: ListMenu::new(items.iter().map(|x| x.to_string()).collect()),
+            pending,
+        }
     }
 }
 
 impl MainMenu {
     fn draw(&mut self, shared: &SharedAssets, window: &mut Window) -> Result<()> {
         window.clear(Color::BLACK)?;
+        let strategy_label = |player, command: &Option<String>| match (player, command) {
+            (Player::Bot(_, _), Some(command)) => {
+                let program = command.split_whitespace().next().unwrap_or_default();
+                let program = program.rsplit(&['/', '\\'][..]).next().unwrap_or_default();
+                format!("AI: {}", program)
+            }
+            (Player::Bot(strategy, _), None) => format!("AI: {}", StrategyId::name(strategy)),
+            (Player::Human, _) => String::new(),
+        };
+        let (left_strategy, right_strategy) = (
+            strategy_label(self.config.left, &self.config.left_command),
+            strategy_label(self.config.right, &self.config.right_command),
+        );
         shared.font.borrow_mut().execute(|f| {
             let mut draw_at_center = |s, center, style| -> Option<Rectangle> {
                 if let Ok(ref text) = f.render(s, style) {
@@ -100,36 +249,34 @@ impl MainMenu {
                 }
             };
 
-            let select = |bot| {
-                if bot {
-                    "Gorilla"
+            let style = |item| {
+                if self.hoover == item {
+                    &shared.hoover_style
                 } else {
-                    "Human"
+                    &shared.default_style
                 }
             };
-
-            let (left, right, play) = match self.hoover {
-                Hoover::Left => (
-                    &shared.hoover_style,
-                    &shared.default_style,
-                    &shared.default_style,
-                ),
-                Hoover::Right => (
-                    &shared.default_style,
-                    &shared.hoover_style,
-                    &shared.default_style,
-                ),
-                Hoover::Play => (
-                    &shared.default_style,
-                    &shared.default_style,
-                    &shared.hoover_style,
-                ),
-                _ => (
-                    &shared.default_style,
-                    &shared.default_style,
-                    &shared.default_style,
-                ),
+            let (left, right, play) = (
+                style(Hoover::Left),
+                style(Hoover::Right),
+                style(Hoover::Play),
+            );
+            let small_style = |item| {
+                if self.hoover == item {
+                    &shared.small_hoover_style
+                } else {
+                    &shared.small_style
+                }
             };
+            let (resume, practice, tournament, rules, settings, statistics, load) = (
+                small_style(Hoover::Continue),
+                small_style(Hoover::Practice),
+                small_style(Hoover::Tournament),
+                small_style(Hoover::Rules),
+                small_style(Hoover::Settings),
+                small_style(Hoover::Statistics),
+                small_style(Hoover::Load),
+            );
 
             draw_at_center(
                 TITLE,
@@ -137,27 +284,151 @@ impl MainMenu {
                 &shared.default_style,
             );
 
+            if let Some(message) = &self.message {
+                draw_at_center(
+                    message,
+                    (WINDOW_X / 2.0, WINDOW_Y / 8.0),
+                    &shared.small_style,
+                );
+            }
+
+            let area_continue = if self.suspended {
+                draw_at_center(CONTINUE, (WINDOW_X / 2.0, WINDOW_Y * 3.0 / 8.0), resume)
+            } else {
+                None
+            };
             let area_left = draw_at_center(
-                select(self.bot_left),
+                self.config.left.name(),
                 (WINDOW_X / 4.0, WINDOW_Y / 2.0),
                 left,
             );
             let area_right = draw_at_center(
-                select(self.bot_right),
+                self.config.right.name(),
                 (WINDOW_X * 3.0 / 4.0, WINDOW_Y / 2.0),
                 right,
             );
+            // bots show their difficulty, and a strategy and a personality that can be switched,
+            // humans the device they play with and a profile that can be switched
+            let mut draw_side =
+                |player, label, personality, profile, input, x, items: [Hoover; 3]| {
+                    let below = WINDOW_Y / 2.0 + MENU_LINE_HEIGHT * 1.5;
+                    match player {
+                        Player::Bot(_, difficulty) => {
+                            draw_at_center(difficulty.name(), (x, below), &shared.small_style);
+                            let strategy = draw_at_center(
+                                label,
+                                (x, below + MENU_LINE_HEIGHT),
+                                small_style(items[0]),
+                            );
+                            let personality = draw_at_center(
+                                personality,
+                                (x, below + MENU_LINE_HEIGHT * 2.0),
+                                small_style(items[1]),
+                            );
+                            vec![(strategy, items[0]), (personality, items[1])]
+                        }
+                        Player::Human => {
+                            draw_at_center(input, (x, below), &shared.small_style);
+                            let name = draw_at_center(
+                                profile,
+                                (x, below + MENU_LINE_HEIGHT),
+                                small_style(items[2]),
+                            );
+                            vec![(name, items[2])]
+                        }
+                    }
+                };
+            let mut side_areas = draw_side(
+                self.config.left,
+                &left_strategy,
+                &self.config.left_personality.name,
+                &self.config.left_profile.name,
+                self.config.left_profile.input.name(),
+                WINDOW_X / 4.0,
+                [
+                    Hoover::LeftStrategy,
+                    Hoover::LeftPersonality,
+                    Hoover::LeftProfile,
+                ],
+            );
+            side_areas.extend(draw_side(
+                self.config.right,
+                &right_strategy,
+                &self.config.right_personality.name,
+                &self.config.right_profile.name,
+                self.config.right_profile.input.name(),
+                WINDOW_X * 3.0 / 4.0,
+                [
+                    Hoover::RightStrategy,
+                    Hoover::RightPersonality,
+                    Hoover::RightProfile,
+                ],
+            ));
             let area_play = draw_at_center(PLAY, (WINDOW_X / 2.0, WINDOW_Y * 3.0 / 4.0), play);
+            let area_practice = draw_at_center(
+                PRACTICE,
+                (
+                    WINDOW_X / 2.0,
+                    WINDOW_Y * 3.0 / 4.0 + MENU_LINE_HEIGHT * 1.5,
+                ),
+                practice,
+            );
+            let area_tournament = draw_at_center(
+                TOURNAMENT,
+                (WINDOW_X / 8.0, WINDOW_Y * 7.0 / 8.0),
+                tournament,
+            );
+            let area_rules =
+                draw_at_center(RULES, (WINDOW_X * 3.0 / 8.0, WINDOW_Y * 7.0 / 8.0), rules);
+            let area_settings = draw_at_center(
+                SETTINGS,
+                (WINDOW_X * 5.0 / 8.0, WINDOW_Y * 7.0 / 8.0),
+                settings,
+            );
+            let area_statistics = draw_at_center(
+                STATISTICS,
+                (WINDOW_X * 7.0 / 8.0, WINDOW_Y * 7.0 / 8.0),
+                statistics,
+            );
+            let area_load = if self.saved {
+                draw_at_center(LOAD, (WINDOW_X / 2.0, WINDOW_Y * 15.0 / 16.0), load)
+            } else {
+                None
+            };
 
             if self.dirty {
-                if let (Some(area_left), Some(area_right), Some(area_play)) =
-                    (area_left, area_right, area_play)
+                if let (Some(area_left), Some(area_right), Some(area_play), Some(area_settings)) =
+                    (area_left, area_right, area_play, area_settings)
                 {
                     self.areas = vec![
                         (area_left, Hoover::Left),
                         (area_right, Hoover::Right),
                         (area_play, Hoover::Play),
-                    ]
+                        (area_settings, Hoover::Settings),
+                    ];
+                    if let Some(area_rules) = area_rules {
+                        self.areas.push((area_rules, Hoover::Rules));
+                    }
+                    if let Some(area_practice) = area_practice {
+                        self.areas.push((area_practice, Hoover::Practice));
+                    }
+                    if let Some(area_tournament) = area_tournament {
+                        self.areas.push((area_tournament, Hoover::Tournament));
+                    }
+                    if let Some(area_statistics) = area_statistics {
+                        self.areas.push((area_statistics, Hoover::Statistics));
+                    }
+                    if let Some(area_continue) = area_continue {
+                        self.areas.push((area_continue, Hoover::Continue));
+                    }
+                    if let Some(area_load) = area_load {
+                        self.areas.push((area_load, Hoover::Load));
+                    }
+                    for (area, item) in side_areas {
+                        if let Some(area) = area {
+                            self.areas.push((area, item));
+                        }
+                    }
                 }
                 self.dirty = false;
             }
@@ -166,7 +437,8 @@ impl MainMenu {
         })
     }
 
-    fn event_hoover(&mut self, pos: Vector) {
+    fn event_hoover(&mut self, pos: Vector, sounds: &mut Vec<Sfx>) {
+        let previous = self.hoover;
         self.hoover = Hoover::None;
         for (area, id) in self.areas.iter() {
             if area.contains(pos) {
@@ -174,35 +446,153 @@ impl MainMenu {
                 break;
             }
         }
+        if self.hoover != previous && self.hoover != Hoover::None {
+            sounds.push(Sfx::MenuMove);
+        }
     }
 
-    fn event(&mut self, event: &Event, _window: &mut Window) -> Option<GameConfig> {
-        match event {
-            Event::Key(Key::Return, ButtonState::Pressed) => Some(GameConfig {
-                bot_left: self.bot_left,
-                bot_right: self.bot_right,
-            }),
-            Event::MouseMoved(pos) => {
-                self.event_hoover(*pos);
+    fn event(&mut self, command: Command, sounds: &mut Vec<Sfx>) -> Option<MenuChoice> {
+        match command {
+            Command::Action(Action::Next, ButtonState::Pressed) => {
+                self.focus_next(sounds, 1);
                 None
             }
-            Event::MouseButton(MouseButton::Left, ButtonState::Pressed) => {
-                self.dirty = true;
-                match self.hoover {
-                    Hoover::Left => {
-                        self.bot_left = !self.bot_left;
-                        None
-                    }
-                    Hoover::Right => {
-                        self.bot_right = !self.bot_right;
-                        None
-                    }
-                    Hoover::Play => Some(GameConfig {
-                        bot_left: self.bot_left,
-                        bot_right: self.bot_right,
-                    }),
-                    _ => None,
+            Command::Action(Action::Previous, ButtonState::Pressed) => {
+                self.focus_next(sounds, MAIN_MENU_ITEMS.len() - 1);
+                None
+            }
+            Command::Action(Action::Confirm, ButtonState::Pressed) => {
+                if self.hoover == Hoover::None {
+                    self.hoover = if self.suspended {
+                        Hoover::Continue
+                    } else {
+                        Hoover::Play
+                    };
                 }
+                self.activate(sounds)
+            }
+            Command::PointerMoved(pos) => {
+                self.event_hoover(pos, sounds);
+                None
+            }
+            Command::PointerClicked => self.activate(sounds),
+            _ => None,
+        }
+    }
+
+    // keyboard and gamepad focus share the mouse hoover highlight
+    fn shown(&self, item: Hoover) -> bool {
+        match item {
+            Hoover::Continue => self.suspended,
+            Hoover::Load => self.saved,
+            Hoover::LeftStrategy | Hoover::LeftPersonality => self.config.left != Player::Human,
+            Hoover::RightStrategy | Hoover::RightPersonality => self.config.right != Player::Human,
+            Hoover::LeftProfile => self.config.left == Player::Human,
+            Hoover::RightProfile => self.config.right == Player::Human,
+            _ => true,
+        }
+    }
+
+    fn focus_next(&mut self, sounds: &mut Vec<Sfx>, step: usize) {
+        let len = MAIN_MENU_ITEMS.len();
+        let mut i = MAIN_MENU_ITEMS
+            .iter()
+            .position(|x| *x == self.hoover)
+            .unwrap_or(len - step % len);
+        loop {
+            i = (i + step) % len;
+            if self.shown(MAIN_MENU_ITEMS[i]) {
+                break;
+            }
+        }
+        self.hoover = MAIN_MENU_ITEMS[i];
+        sounds.push(Sfx::MenuMove);
+    }
+
+    fn activate(&mut self, sounds: &mut Vec<Sfx>) -> Option<MenuChoice> {
+        self.dirty = true;
+        match self.hoover {
+            Hoover::Left => {
+                sounds.push(Sfx::MenuSelect);
+                self.config.left = self.config.left.next();
+                None
+            }
+            Hoover::Right => {
+                sounds.push(Sfx::MenuSelect);
+                self.config.right = self.config.right.next();
+                None
+            }
+            Hoover::LeftStrategy => {
+                sounds.push(Sfx::MenuSelect);
+                self.config.left = self.config.left.next_strategy();
+                None
+            }
+            Hoover::RightStrategy => {
+                sounds.push(Sfx::MenuSelect);
+                self.config.right = self.config.right.next_strategy();
+                None
+            }
+            Hoover::LeftPersonality => {
+                sounds.push(Sfx::MenuSelect);
+                if let Some(next) = named::next(&self.personalities, &self.config.left_personality)
+                {
+                    self.config.left_personality = next.clone();
+                }
+                None
+            }
+            Hoover::RightPersonality => {
+                sounds.push(Sfx::MenuSelect);
+                if let Some(next) = named::next(&self.personalities, &self.config.right_personality)
+                {
+                    self.config.right_personality = next.clone();
+                }
+                None
+            }
+            Hoover::LeftProfile => {
+                sounds.push(Sfx::MenuSelect);
+                if let Some(next) = named::next(&self.profiles, &self.config.left_profile) {
+                    self.config.left_profile = next.clone();
+                }
+                None
+            }
+            Hoover::RightProfile => {
+                sounds.push(Sfx::MenuSelect);
+                if let Some(next) = named::next(&self.profiles, &self.config.right_profile) {
+                    self.config.right_profile = next.clone();
+                }
+                None
+            }
+            Hoover::Play => {
+                sounds.push(Sfx::MenuSelect);
+                Some(MenuChoice::Play(Box::new(self.config.clone())))
+            }
+            Hoover::Continue => {
+                sounds.push(Sfx::MenuSelect);
+                Some(MenuChoice::Continue)
+            }
+            Hoover::Settings => {
+                sounds.push(Sfx::MenuSelect);
+                Some(MenuChoice::Settings)
+            }
+            Hoover::Practice => {
+                sounds.push(Sfx::MenuSelect);
+                Some(MenuChoice::Practice)
+            }
+            Hoover::Tournament => {
+                sounds.push(Sfx::MenuSelect);
+                Some(MenuChoice::Tournament)
+            }
+            Hoover::Rules => {
+                sounds.push(Sfx::MenuSelect);
+                Some(MenuChoice::Rules)
+            }
+            Hoover::Statistics => {
+                sounds.push(Sfx::MenuSelect);
+                Some(MenuChoice::Statistics)
+            }
+            Hoover::Load => {
+                sounds.push(Sfx::MenuSelect);
+                Some(MenuChoice::Load)
             }
             _ => None,
         }
@@ -211,39 +601,95 @@ impl MainMenu {
 
 impl State for States {
     fn new() -> Result<States> {
-        let mut parts = vec![];
-        for _ in 0..11 {
-            parts.push(Vec::with_capacity(512));
-        }
-
-        Ok(States {
-            shared_assets: SharedAssets {
-                explosion: RefCell::new(Asset::new(Image::load("Explosion.png"))),
-                sky: RefCell::new(Asset::new(Image::load("Sky.png"))),
-                building_tiles: RefCell::new(Asset::new(Image::load("Buildings.png"))),
-                player_tiles: RefCell::new(Asset::new(Image::load("Bowtie.png"))),
-                font: RefCell::new(Asset::new(Font::load("UI.ttf"))),
-                default_style: FontStyle::new(64.0, Color::WHITE),
-                hoover_style: FontStyle::new(64.0, Color::RED),
-            },
-            shared_data: SharedData {
-                particle_buffer: Vec::with_capacity(PARTICLE_COUNT),
-                parts,
-            },
+        let options = OPTIONS.get_or_init(Options::default);
+        let config = Config::load(&options.config_path);
+        let game_config = GameConfig {
+            left: options.left,
+            right: options.right,
+            match_length: options.rounds,
+            seed: options.seed,
+            left_command: options.left_command.clone(),
+            right_command: options.right_command.clone(),
+            headless: false,
+            left_personality: named::pick(
+                &config.personalities,
+                &options.left_personality,
+                0,
+                "personality",
+            ),
+            right_personality: named::pick(
+                &config.personalities,
+                &options.right_personality,
+                0,
+                "personality",
+            ),
+            left_profile: named::pick(&config.profiles, &options.left_profile, 0, "profile"),
+            right_profile: named::pick(&config.profiles, &options.right_profile, 1, "profile"),
+            rules: options.rules(config.rules),
+            ballistics: config.ballistics,
+            practice: None,
+        };
+        let personalities = config.personalities.clone();
+        let profiles = config.profiles.clone();
+        let settings_menu = SettingsMenu::new(&config.audio, &config.ballistics);
+        let controls_menu = ControlsMenu::new(&config.bindings);
+        let stats = Stats::load(STATS_PATH);
+        let stats_menu = StatsMenu::new(&stats);
+        let rules_menu = RulesMenu::new(&config.rules);
+        let practice_menu = PracticeMenu::new(&config.practice);
+        let mut states = States {
+            config,
+            config_path: options.config_path.clone(),
+            view_ready: false,
+            audio: Audio::new(),
+            shared_assets: SharedAssets::new(),
+            shared_data: SharedData::new(),
             focus: Focus::Main,
             game: None,
-            pause_menu: PauseMenu,
+            pause_menu: PauseMenu::new(),
             main_menu: MainMenu {
-                bot_left: false,
-                bot_right: false,
+                config: game_config.clone(),
+                personalities,
+                profiles,
                 dirty: true,
+                suspended: false,
+                saved: save::exists(SAVE_PATH),
+                message: None,
                 hoover: Hoover::None,
                 areas: vec![],
             },
-        })
+            settings_menu,
+            controls_menu,
+            stats,
+            stats_menu,
+            rules_menu,
+            practice_menu,
+            recorded: false,
+            undo_stats: None,
+            tournament: None,
+            tournament_menu: None,
+            tournament_setup: None,
+            tournament_match: None,
+            result_frames: 0,
+            confirm_menu: None,
+            settings_return: Focus::Main,
+        };
+        if !options.tournament.is_empty() {
+            states.begin_tournament(&options.tournament, options.format);
+        } else if options.play {
+            states.start_game(game_config)?;
+        } else if options.practice {
+            states.start_game(states.practice_config())?;
+        }
+        Ok(states)
     }
 
     fn draw(&mut self, window: &mut Window) -> Result<()> {
+        // everything is laid out for the default size and scaled to the actual window
+        if !self.view_ready {
+            window.set_view(View::new(Rectangle::new_sized((WINDOW_X, WINDOW_Y))));
+            self.view_ready = true;
+        }
         match self.focus {
             Focus::Main => self.main_menu.draw(&self.shared_assets, window),
             Focus::Game => {
@@ -258,56 +704,607 @@ impl State for States {
                 }
                 self.pause_menu.draw(&self.shared_assets, window)
             }
+            Focus::Settings => self.settings_menu.draw(&self.shared_assets, window),
+            Focus::Controls => self.controls_menu.draw(&self.shared_assets, window),
+            Focus::Statistics => self.stats_menu.draw(&self.shared_assets, window),
+            Focus::Rules => self.rules_menu.draw(&self.shared_assets, window),
+            Focus::Practice => self.practice_menu.draw(&self.shared_assets, window),
+            Focus::Tournament => match &mut self.tournament_menu {
+                Some(menu) => menu.draw(&self.shared_assets, window),
+                None => Ok(()),
+            },
+            Focus::TournamentSetup => match &mut self.tournament_setup {
+                Some(menu) => menu.draw(&self.shared_assets, window),
+                None => Ok(()),
+            },
+            Focus::Confirm => {
+                if let Some(menu) = &mut self.confirm_menu {
+                    match (&menu.pending, &mut self.game) {
+                        (Confirm::Quit, Some(game)) => {
+                            game.draw(&self.shared_assets, &self.shared_data, window)?;
+                            draw_overlay(window);
+                        }
+                        _ => window.clear(Color::BLACK)?,
+                    }
+                    menu.list.draw(menu.title, &self.shared_assets, window)?;
+                }
+                Ok(())
+            }
         }
     }
 
-    fn event(&mut self, event: &Event, window: &mut Window) -> Result<()> {
-        match (event, &self.focus) {
-            // main menu
-            (_, Focus::Main) => {
-                if let Some(config) = self.main_menu.event(event, window) {
-                    self.game = Some(Game::new(config, &mut self.shared_data.parts)?);
-                    self.focus = Focus::Game;
+    fn event(&mut self, event: &Event, _window: &mut Window) -> Result<()> {
+        let sounds = &mut self.shared_data.sounds;
+        // the rebinding screen needs the raw events, even the ones bound to global actions
+        if let Focus::Controls = self.focus {
+            if self
+                .controls_menu
+                .event(event, &mut self.config.bindings, sounds)
+            {
+                self.save_config();
+                self.focus = Focus::Settings;
+            }
+            return Ok(());
+        }
+
+        let bindings = &self.config.bindings;
+        if let Some(Command::Action(action, ButtonState::Pressed)) =
+            bindings.command(event, &GLOBAL_ACTIONS)
+        {
+            self.event_audio(action);
+            return Ok(());
+        }
+
+        match self.focus {
+            Focus::Main => {
+                let suspended = self.game.is_some();
+                let choice = match bindings.command(event, &MENU_ACTIONS) {
+                    Some(command) => self.main_menu.event(command, sounds),
+                    None => None,
+                };
+                match choice {
+                    Some(MenuChoice::Continue) if suspended => self.focus = Focus::Game,
+                    Some(MenuChoice::Load) => {
+                        if suspended {
+                            self.open_confirm(Confirm::Load);
+                        } else {
+                            self.load_game();
+                        }
+                    }
+                    Some(MenuChoice::Play(config)) => {
+                        if suspended {
+                            self.open_confirm(Confirm::NewGame(config));
+                        } else {
+                            self.start_game(*config)?;
+                        }
+                    }
+                    Some(MenuChoice::Settings) => self.open_settings(),
+                    Some(MenuChoice::Practice) => {
+                        self.practice_menu = PracticeMenu::new(&self.config.practice);
+                        self.focus = Focus::Practice;
+                    }
+                    Some(MenuChoice::Tournament) => self.open_tournament(),
+                    Some(MenuChoice::Rules) => {
+                        self.rules_menu = RulesMenu::new(&self.config.rules);
+                        self.focus = Focus::Rules;
+                    }
+                    Some(MenuChoice::Statistics) => {
+                        self.stats_menu = StatsMenu::new(&self.stats);
+                        self.focus = Focus::Statistics;
+                    }
+                    _ => (),
                 }
-                Ok(())
             }
-            // pause
-            (Event::Key(Key::Space, ButtonState::Pressed), Focus::Pause) => {
-                self.focus = Focus::Game;
-                Ok(())
+            Focus::Pause => {
+                let choice = match bindings.command(event, &PAUSE_ACTIONS) {
+                    Some(command) => self.pause_menu.event(command, sounds),
+                    None => None,
+                };
+                match choice {
+                    Some(PauseChoice::Resume) => self.focus = Focus::Game,
+                    Some(PauseChoice::Restart) => {
+                        if self.game.as_ref().is_some_and(Game::round_decided) {
+                            self.undo_round();
+                        }
+                        if let Some(game) = &mut self.game {
+                            game.restart_round(&mut self.shared_data);
+                        }
+                        self.focus = Focus::Game;
+                    }
+                    Some(PauseChoice::Save) => self.save_game(),
+                    Some(PauseChoice::Settings) => self.open_settings(),
+                    Some(PauseChoice::Quit) => self.open_confirm(Confirm::Quit),
+                    None => (),
+                }
             }
-            // game
-            (Event::Key(Key::Escape, ButtonState::Pressed), Focus::Game) => {
-                self.focus = Focus::Main;
-                Ok(())
+            Focus::Settings => {
+                let choice = match bindings.command(event, &MENU_ACTIONS) {
+                    Some(command) => self.settings_menu.event(
+                        command,
+                        &mut self.config.audio,
+                        &mut self.config.ballistics,
+                        sounds,
+                    ),
+                    None => None,
+                };
+                match choice {
+                    Some(SettingsEvent::Controls) => {
+                        self.controls_menu = ControlsMenu::new(&self.config.bindings);
+                        self.focus = Focus::Controls;
+                    }
+                    Some(SettingsEvent::Back) => {
+                        self.save_config();
+                        self.main_menu.config.ballistics = self.config.ballistics;
+                        if let Some(game) = self.game.as_mut() {
+                            game.set_ballistics(self.config.ballistics);
+                        }
+                        match self.settings_return {
+                            Focus::Main => self.show_main(),
+                            focus => self.focus = focus,
+                        }
+                    }
+                    None => (),
+                }
             }
-            (Event::Key(Key::Space, ButtonState::Pressed), Focus::Game) => {
-                self.focus = Focus::Pause;
-                Ok(())
+            Focus::Statistics => {
+                if let Some(command) = bindings.command(event, &MENU_ACTIONS) {
+                    if self.stats_menu.event(command, &self.stats, sounds) {
+                        self.show_main();
+                    }
+                }
             }
-            (_, Focus::Game) => {
-                if let Some(game) = &mut self.game {
-                    game.event(event);
+            Focus::Rules => {
+                if let Some(command) = bindings.command(event, &MENU_ACTIONS) {
+                    if self
+                        .rules_menu
+                        .event(command, &mut self.config.rules, sounds)
+                    {
+                        self.save_config();
+                        // the flags of this run still apply over the edited rules
+                        let options = OPTIONS.get_or_init(Options::default);
+                        self.main_menu.config.rules = options.rules(self.config.rules);
+                        self.show_main();
+                    }
+                }
+            }
+            Focus::Practice => {
+                let choice = match bindings.command(event, &MENU_ACTIONS) {
+                    Some(command) => {
+                        self.practice_menu
+                            .event(command, &mut self.config.practice, sounds)
+                    }
+                    None => None,
+                };
+                match choice {
+                    Some(PracticeEvent::Start) => {
+                        self.save_config();
+                        let config = self.practice_config();
+                        if self.game.is_some() {
+                            self.open_confirm(Confirm::NewGame(Box::new(config)));
+                        } else {
+                            self.start_game(config)?;
+                        }
+                    }
+                    Some(PracticeEvent::Back) => {
+                        self.save_config();
+                        self.show_main();
+                    }
+                    None => (),
+                }
+            }
+            Focus::Tournament => {
+                let choice = match (
+                    &mut self.tournament_menu,
+                    bindings.command(event, &MENU_ACTIONS),
+                ) {
+                    (Some(menu), Some(command)) => menu.event(command, sounds),
+                    _ => None,
+                };
+                match choice {
+                    Some(TournamentEvent::Play) => self.play_next_match()?,
+                    Some(TournamentEvent::New) => {
+                        self.tournament = None;
+                        self.open_tournament();
+                    }
+                    Some(TournamentEvent::Export) => self.export_tournament(),
+                    Some(TournamentEvent::Back) => self.show_main(),
+                    None => (),
+                }
+            }
+            Focus::TournamentSetup => {
+                let choice = match (
+                    &mut self.tournament_setup,
+                    bindings.command(event, &MENU_ACTIONS),
+                ) {
+                    (Some(menu), Some(command)) => menu.event(command, sounds),
+                    _ => None,
+                };
+                match choice {
+                    Some(SetupEvent::Start(tournament)) => {
+                        self.tournament = Some(tournament);
+                        self.open_tournament();
+                    }
+                    Some(SetupEvent::Back) => self.show_main(),
+                    None => (),
                 }
-                Ok(())
             }
-            _ => Ok(()),
+            Focus::Confirm => self.event_confirm(event)?,
+            Focus::Game => match bindings.command(event, &GAME_ACTIONS) {
+                Some(Command::Action(Action::Pause, ButtonState::Pressed))
+                | Some(Command::Action(Action::Back, ButtonState::Pressed)) => {
+                    self.pause_menu = PauseMenu::new();
+                    self.pause_menu.list.focus = Some(0);
+                    self.focus = Focus::Pause
+                }
+                // the player whose turn it is may only play with their own device
+                Some(command) => {
+                    if let Some(game) = self.game.as_mut().filter(|x| x.accepts(event)) {
+                        game.event(command, &mut self.shared_data);
+                    }
+                }
+                None => (),
+            },
+            Focus::Controls => (),
         }
+        Ok(())
     }
 
     fn update(&mut self, window: &mut Window) -> Result<()> {
-        match self.focus {
-            Focus::Game => {
-                if let Some(game) = &mut self.game {
-                    game.update(&mut self.shared_data, window)?;
+        if let Focus::Game = self.focus {
+            if let Some(game) = &mut self.game {
+                game.update(&mut self.shared_data, window)?;
+            }
+            self.record_rounds();
+            self.update_tournament();
+        }
+        for sfx in self.shared_data.sounds.drain(..) {
+            self.audio.play(sfx, &self.config.audio);
+        }
+        self.audio.update(&self.config.audio);
+        Ok(())
+    }
+}
+
+impl States {
+    fn start_game(&mut self, config: GameConfig) -> Result<()> {
+        self.end_match();
+        self.game = Some(Game::new(config, &mut self.shared_data.parts)?);
+        self.recorded = false;
+        self.undo_stats = None;
+        self.tournament_match = None;
+        self.result_frames = 0;
+        self.main_menu.message = None;
+        self.focus = Focus::Game;
+        Ok(())
+    }
+
+    // the players picked in the main menu, with a human on the left throwing at the range
+    fn practice_config(&self) -> GameConfig {
+        let mut config = self.main_menu.config.clone();
+        config.left = Player::Human;
+        config.match_length = None;
+        config.practice = Some(self.config.practice);
+        config
+    }
+
+    fn show_main(&mut self) {
+        let menu = &mut self.main_menu;
+        menu.suspended = self.game.is_some();
+        menu.saved = save::exists(SAVE_PATH);
+        if !menu.shown(menu.hoover) {
+            menu.hoover = Hoover::None;
+        }
+        menu.dirty = true;
+        self.focus = Focus::Main;
+    }
+
+    fn save_game(&mut self) {
+        if let Some(game) = &self.game {
+            let result = save::save(SAVE_PATH, game, &self.shared_data.parts);
+            self.pause_menu.list.items[2] = match result {
+                Ok(()) => "Match saved".to_string(),
+                Err(e) => {
+                    eprintln!("Failed to save {}: {}", SAVE_PATH, e);
+                    "Saving failed".to_string()
                 }
-                Ok(())
+            };
+        }
+    }
+
+    fn load_game(&mut self) {
+        match save::load(SAVE_PATH, &mut self.shared_data.parts) {
+            Ok(game) => {
+                self.end_match();
+                self.game = Some(game);
+                self.recorded = false;
+                self.undo_stats = None;
+                self.tournament_match = None;
+                self.main_menu.message = None;
+                self.focus = Focus::Game;
+            }
+            Err(e) => {
+                eprintln!("Failed to load {}: {}", SAVE_PATH, e);
+                self.main_menu.message = Some(format!("Can not load: {}", e));
+                self.show_main();
+            }
+        }
+    }
+
+    fn open_settings(&mut self) {
+        self.settings_return = self.focus;
+        self.settings_menu = SettingsMenu::new(&self.config.audio, &self.config.ballistics);
+        self.focus = Focus::Settings;
+    }
+
+    fn open_confirm(&mut self, pending: Confirm) {
+        self.confirm_menu = Some(ConfirmMenu::new(pending));
+        self.focus = Focus::Confirm;
+    }
+
+    fn event_confirm(&mut self, event: &Event) -> Result<()> {
+        let selected = match (
+            &mut self.confirm_menu,
+            self.config.bindings.command(event, &MENU_ACTIONS),
+        ) {
+            (Some(menu), Some(command)) => {
+                match menu.list.event(command, &mut self.shared_data.sounds) {
+                    Some(MenuEvent::Selected(i)) => Some(i),
+                    Some(MenuEvent::Back) => None,
+                    None => return Ok(()),
+                }
+            }
+            _ => return Ok(()),
+        };
+
+        match (self.confirm_menu.take().map(|x| x.pending), selected) {
+            // the suspended match is picked up again with continue
+            (Some(Confirm::Quit), Some(0)) => self.show_main(),
+            (Some(Confirm::Quit), Some(1)) => {
+                self.end_match();
+                self.game = None;
+                self.tournament_match = None;
+                self.result_frames = 0;
+                self.show_main();
+            }
+            (Some(Confirm::Quit), _) => self.focus = Focus::Pause,
+            (Some(Confirm::NewGame(config)), Some(0)) => self.start_game(*config)?,
+            (Some(Confirm::Load), Some(0)) => self.load_game(),
+            (Some(Confirm::NextMatch), Some(0)) => self.start_tournament_match()?,
+            _ => self.show_main(),
+        }
+        Ok(())
+    }
+
+    // from the command line, entries are profile names or bots as in --left
+    fn begin_tournament(&mut self, entries: &[String], format: Format) {
+        let mut entrants = vec![];
+        for entry in entries {
+            if let Some(found) = named::find(&self.config.profiles, entry) {
+                entrants.push(Entrant::human(found));
+                continue;
+            }
+            match cli::player("--tournament", Some(entry.clone())) {
+                Ok(Player::Bot(strategy, difficulty)) => {
+                    entrants.push(Entrant::bot(strategy, difficulty))
+                }
+                _ => eprintln!("Unknown tournament player {}, leaving it out", entry),
+            }
+        }
+        match Tournament::new(format, entrants) {
+            Ok(tournament) => {
+                self.tournament = Some(tournament);
+                self.open_tournament();
+            }
+            Err(e) => eprintln!("Can not start the tournament: {}", e),
+        }
+    }
+
+    // the running tournament, or a new one to set up
+    fn open_tournament(&mut self) {
+        match &self.tournament {
+            Some(tournament) => {
+                self.tournament_menu = Some(TournamentMenu::new(tournament));
+                self.focus = Focus::Tournament;
+            }
+            None => {
+                // every profile and every bot can take part once
+                let mut candidates = self
+                    .config
+                    .profiles
+                    .iter()
+                    .map(Entrant::human)
+                    .collect::<Vec<_>>();
+                for strategy in STRATEGIES.iter().filter_map(|x| StrategyId::find(x.name)) {
+                    for difficulty in [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard] {
+                        candidates.push(Entrant::bot(strategy, difficulty));
+                    }
+                }
+                self.tournament_setup = Some(TournamentSetup::new(candidates));
+                self.focus = Focus::TournamentSetup;
             }
-            _ => Ok(()),
         }
     }
+
+    // a suspended match is either the tournament match itself or has to be abandoned
+    fn play_next_match(&mut self) -> Result<()> {
+        let next = self.tournament.as_ref().and_then(|x| x.next_match());
+        match (&self.game, self.tournament_match) {
+            (Some(_), Some(at)) if Some(at) == next => self.focus = Focus::Game,
+            (Some(_), _) => self.open_confirm(Confirm::NextMatch),
+            (None, _) => self.start_tournament_match()?,
+        }
+        Ok(())
+    }
+
+    fn start_tournament_match(&mut self) -> Result<()> {
+        let tournament = match &self.tournament {
+            Some(tournament) => tournament,
+            None => return Ok(()),
+        };
+        let at = match tournament.next_match() {
+            Some(at) => at,
+            None => return Ok(()),
+        };
+        let pairing = tournament.pairing(at);
+        let left = &tournament.entrants[pairing.left];
+        let right = &tournament.entrants[pairing.right.unwrap_or(pairing.left)];
+        // the bots keep the personalities picked in the main menu
+        let menu = &self.main_menu.config;
+        let config = GameConfig {
+            left: left.player,
+            right: right.player,
+            match_length: Some(menu.match_length.unwrap_or(TOURNAMENT_MATCH_LENGTH)),
+            seed: None,
+            left_command: None,
+            right_command: None,
+            headless: false,
+            left_personality: menu.left_personality.clone(),
+            right_personality: menu.right_personality.clone(),
+            left_profile: left.profile.clone(),
+            right_profile: right.profile.clone(),
+            rules: menu.rules,
+            ballistics: menu.ballistics,
+            practice: None,
+        };
+        self.start_game(config)?;
+        self.tournament_match = Some(at);
+        Ok(())
+    }
+
+    // a decided tournament match shows its result for a moment before the standings
+    fn update_tournament(&mut self) {
+        if self.result_frames > 0 {
+            self.result_frames -= 1;
+            if self.result_frames == 0 {
+                self.game = None;
+                self.open_tournament();
+            }
+            return;
+        }
+        let (game, at) = match (&self.game, self.tournament_match) {
+            (Some(game), Some(at)) => (game, at),
+            _ => return,
+        };
+        if let (Some(winner), true) = (game.winner(), game.is_over()) {
+            if let Some(tournament) = &mut self.tournament {
+                tournament.record(at, game.points(), winner);
+            }
+            self.tournament_match = None;
+            self.result_frames = (TOURNAMENT_RESULT_MS / DELTAT_MS) as u32;
+        }
+    }
+
+    fn export_tournament(&mut self) {
+        let (tournament, menu) = match (&self.tournament, &mut self.tournament_menu) {
+            (Some(tournament), Some(menu)) => (tournament, menu),
+            _ => return,
+        };
+        match tournament.export(TOURNAMENT_PATH) {
+            Ok(()) => menu.exported(format!("Results saved to {}", TOURNAMENT_PATH)),
+            Err(e) => {
+                eprintln!("Failed to save {}: {}", TOURNAMENT_PATH, e);
+                menu.exported("Export failed".to_string());
+            }
+        }
+    }
+
+    // finished rounds go into the statistics as they come, the match once it is decided
+    // a decided round can still be restarted until the next one begins
+    fn record_rounds(&mut self) {
+        let game = match &self.game {
+            Some(game) => game,
+            None => return,
+        };
+        if game.is_practice() {
+            self.shared_data.rounds.clear();
+            return;
+        }
+        if !game.round_decided() {
+            self.undo_stats = None;
+        }
+        let (left, right) = (game.player_name(Side::Left), game.player_name(Side::Right));
+        let mut changed = false;
+        if !self.shared_data.rounds.is_empty() {
+            self.undo_stats = Some(self.stats.clone());
+        }
+        for round in self.shared_data.rounds.drain(..) {
+            self.stats.add_round(&left, &right, &round);
+            changed = true;
+        }
+        if game.is_over() && !self.recorded {
+            self.stats.add_match(&left, &right, game.winner());
+            self.recorded = true;
+            changed = true;
+        }
+        if changed {
+            self.save_stats();
+        }
+    }
+
+    // the restarted round is played again, it and the match it decided leave the statistics
+    fn undo_round(&mut self) {
+        if let Some(stats) = self.undo_stats.take() {
+            self.stats = stats;
+            self.recorded = false;
+            self.save_stats();
+        }
+    }
+
+    // a match left before it was decided still counts as played
+    fn end_match(&mut self) {
+        let game = match &self.game {
+            Some(game) => game,
+            None => return,
+        };
+        if self.recorded || game.rounds_played() == 0 || game.is_practice() {
+            return;
+        }
+        let (left, right) = (game.player_name(Side::Left), game.player_name(Side::Right));
+        self.stats.add_match(&left, &right, game.result());
+        self.recorded = true;
+        self.save_stats();
+    }
+
+    fn save_stats(&self) {
+        if let Err(e) = self.stats.save(STATS_PATH) {
+            eprintln!("Failed to save {}: {}", STATS_PATH, e);
+        }
+    }
+
+    fn save_config(&self) {
+        if let Err(e) = self.config.save(&self.config_path) {
+            eprintln!("Failed to save {}: {}", self.config_path, e);
+        }
+    }
+
+    // volume actions work on every screen
+    fn event_audio(&mut self, action: Action) {
+        let audio = &mut self.config.audio;
+        match action {
+            Action::Mute => audio.muted = !audio.muted,
+            Action::VolumeDown => {
+                audio.master_volume = (audio.master_volume - VOLUME_STEP).max(0.0)
+            }
+            Action::VolumeUp => audio.master_volume = (audio.master_volume + VOLUME_STEP).min(1.0),
+            _ => return,
+        }
+        self.save_config();
+    }
 }
 
 fn main() {
-    run::<States>("Gorillas-rs", Vector::new(800, 600), Settings::default());
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return;
+    }
+    let options = OPTIONS.get_or_init(|| options);
+    let size = Vector::new(options.window.0, options.window.1);
+    run::<States>("Gorillas-rs", size, Settings::default());
 }
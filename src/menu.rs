@@ -0,0 +1,700 @@
+use crate::audio::Sfx;
+use crate::ballistics::{self, Ballistics};
+use crate::config::AudioConfig;
+use crate::game_constants::*;
+use crate::input::*;
+use crate::practice::Practice;
+use crate::rules::Rules;
+use crate::stats::{Record, Stats};
+use crate::tournament::{Entrant, Format, Tournament};
+use crate::SharedAssets;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Transform},
+    graphics::{
+        Background::{Col, Img},
+        Color,
+    },
+    input::{ButtonState, Key},
+    lifecycle::{Event, Window},
+    Result,
+};
+
+pub enum MenuEvent {
+    Selected(usize),
+    Back,
+}
+
+pub enum SettingsEvent {
+    Controls,
+    Back,
+}
+
+pub enum PracticeEvent {
+    Start,
+    Back,
+}
+
+// a vertical list of entries, driven by the pointer or the menu actions
+pub struct ListMenu {
+    pub items: Vec<String>,
+    pub focus: Option<usize>,
+    // where the first entry is drawn
+    pub top: f32,
+    areas: Vec<Rectangle>,
+}
+
+pub struct SettingsMenu {
+    list: ListMenu,
+}
+
+pub struct RulesMenu {
+    list: ListMenu,
+}
+
+pub struct PracticeMenu {
+    list: ListMenu,
+}
+
+// one player's record at a time, the first entry switches to the next player
+pub struct StatsMenu {
+    list: ListMenu,
+    names: Vec<String>,
+    shown: usize,
+}
+
+pub enum TournamentEvent {
+    Play,
+    New,
+    Export,
+    Back,
+}
+
+pub enum SetupEvent {
+    Start(Tournament),
+    Back,
+}
+
+// the bracket or the standings between the matches, with what to do next below
+pub struct TournamentMenu {
+    lines: Vec<String>,
+    list: ListMenu,
+    // a match is left to play, otherwise the first entry starts a new tournament
+    playing: bool,
+}
+
+// picks the format and who takes part
+pub struct TournamentSetup {
+    list: ListMenu,
+    format: Format,
+    candidates: Vec<Entrant>,
+    chosen: Vec<bool>,
+}
+
+pub struct ControlsMenu {
+    list: ListMenu,
+    listening: Option<Action>,
+}
+
+impl ListMenu {
+    pub fn new(items: Vec<String>) -> Self {
+        ListMenu {
+            items,
+            focus: None,
+            top: MENU_TOP,
+            areas: vec![],
+        }
+    }
+
+    pub fn draw(&mut self, title: &str, shared: &SharedAssets, window: &mut Window) -> Result<()> {
+        let items = &self.items;
+        let focus = self.focus;
+        let top = self.top;
+        let areas = &mut self.areas;
+        shared.font.borrow_mut().execute(|f| {
+            if let Ok(ref text) = f.render(title, &shared.default_style) {
+                window.draw_ex(
+                    &text.area().with_center((WINDOW_X / 2.0, MENU_TITLE_Y)),
+                    Img(text),
+                    Transform::IDENTITY,
+                    7.0,
+                );
+            }
+            areas.clear();
+            for (i, item) in items.iter().enumerate() {
+                let style = if focus == Some(i) {
+                    &shared.small_hoover_style
+                } else {
+                    &shared.small_style
+                };
+                let center = (WINDOW_X / 2.0, top + i as f32 * MENU_LINE_HEIGHT);
+                if let Ok(ref text) = f.render(item, style) {
+                    let rect = text.area().with_center(center);
+                    window.draw_ex(&rect, Img(text), Transform::IDENTITY, 7.0);
+                    areas.push(rect);
+                } else {
+                    eprintln!("Failed to render: {}", item);
+                }
+            }
+            Ok(())
+        })
+    }
+
+    pub fn event(&mut self, command: Command, sounds: &mut Vec<Sfx>) -> Option<MenuEvent> {
+        let len = self.items.len();
+        match command {
+            Command::Action(Action::Next, ButtonState::Pressed) => {
+                self.focus = Some(self.focus.map_or(0, |i| (i + 1) % len));
+                sounds.push(Sfx::MenuMove);
+                None
+            }
+            Command::Action(Action::Previous, ButtonState::Pressed) => {
+                self.focus = Some(self.focus.map_or(len - 1, |i| (i + len - 1) % len));
+                sounds.push(Sfx::MenuMove);
+                None
+            }
+            Command::Action(Action::Back, ButtonState::Pressed) => {
+                sounds.push(Sfx::MenuSelect);
+                Some(MenuEvent::Back)
+            }
+            Command::Action(Action::Confirm, ButtonState::Pressed) | Command::PointerClicked => {
+                let selected = self.focus?;
+                sounds.push(Sfx::MenuSelect);
+                Some(MenuEvent::Selected(selected))
+            }
+            Command::PointerMoved(pos) => {
+                let hoover = self.areas.iter().position(|area| area.contains(pos));
+                if hoover.is_some() && hoover != self.focus {
+                    sounds.push(Sfx::MenuMove);
+                }
+                if hoover.is_some() {
+                    self.focus = hoover;
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+// darkens whatever was drawn below a menu
+pub fn draw_overlay(window: &mut Window) {
+    window.draw_ex(
+        &Rectangle::new_sized((WINDOW_X, WINDOW_Y)),
+        Col(Color::BLACK.with_alpha(OVERLAY_ALPHA)),
+        Transform::IDENTITY,
+        6.5,
+    );
+}
+
+fn percent(volume: f32) -> i32 {
+    (volume * 100.0).round() as i32
+}
+
+// steps the volume up and wraps around to silence
+fn cycle_volume(volume: f32) -> f32 {
+    let next = percent(volume) + percent(VOLUME_STEP);
+    if next > 100 {
+        0.0
+    } else {
+        next as f32 / 100.0
+    }
+}
+
+impl SettingsMenu {
+    pub fn new(audio: &AudioConfig, ballistics: &Ballistics) -> Self {
+        let mut menu = SettingsMenu {
+            list: ListMenu::new(vec![]),
+        };
+        menu.refresh(audio, ballistics);
+        menu
+    }
+
+    fn refresh(&mut self, audio: &AudioConfig, ballistics: &Ballistics) {
+        let sound = if audio.muted { "off" } else { "on" };
+        self.list.items = vec![
+            format!("Sound: {}", sound),
+            format!("Master volume: {}%", percent(audio.master_volume)),
+            format!("Effects volume: {}%", percent(audio.sfx_volume)),
+            format!("Music volume: {}%", percent(audio.music_volume)),
+            format!(
+                "Air drag: {}",
+                ballistics::level_name(ballistics.drag, &DRAG_CHOICES)
+            ),
+            format!(
+                "Spin: {}",
+                ballistics::level_name(ballistics.magnus, &MAGNUS_CHOICES)
+            ),
+            "Controls".to_string(),
+            "Back".to_string(),
+        ];
+    }
+
+    pub fn draw(&mut self, shared: &SharedAssets, window: &mut Window) -> Result<()> {
+        window.clear(Color::BLACK)?;
+        self.list.draw("Settings", shared, window)
+    }
+
+    pub fn event(
+        &mut self,
+        command: Command,
+        audio: &mut AudioConfig,
+        ballistics: &mut Ballistics,
+        sounds: &mut Vec<Sfx>,
+    ) -> Option<SettingsEvent> {
+        match self.list.event(command, sounds)? {
+            MenuEvent::Selected(0) => audio.muted = !audio.muted,
+            MenuEvent::Selected(1) => audio.master_volume = cycle_volume(audio.master_volume),
+            MenuEvent::Selected(2) => audio.sfx_volume = cycle_volume(audio.sfx_volume),
+            MenuEvent::Selected(3) => audio.music_volume = cycle_volume(audio.music_volume),
+            MenuEvent::Selected(4) => {
+                ballistics.drag = ballistics::cycle(ballistics.drag, &DRAG_CHOICES)
+            }
+            MenuEvent::Selected(5) => {
+                ballistics.magnus = ballistics::cycle(ballistics.magnus, &MAGNUS_CHOICES)
+            }
+            MenuEvent::Selected(6) => return Some(SettingsEvent::Controls),
+            _ => return Some(SettingsEvent::Back),
+        }
+        self.refresh(audio, ballistics);
+        None
+    }
+}
+
+// steps through the choices and back to no clock at all
+fn cycle_shot_clock(seconds: Option<u32>) -> Option<u32> {
+    let next = match seconds {
+        Some(seconds) => SHOT_CLOCK_CHOICES.iter().find(|x| **x > seconds),
+        None => SHOT_CLOCK_CHOICES.first(),
+    };
+    next.cloned()
+}
+
+fn cycle_sudden_death(turns: Option<u32>) -> Option<u32> {
+    let next = match turns {
+        Some(turns) => SUDDEN_DEATH_CHOICES.iter().find(|x| **x > turns),
+        None => SUDDEN_DEATH_CHOICES.first(),
+    };
+    next.cloned()
+}
+
+fn cycle_bounces(bounces: u32) -> u32 {
+    let next = RICOCHET_BOUNCE_CHOICES.iter().find(|x| **x > bounces);
+    *next.unwrap_or(&RICOCHET_BOUNCE_CHOICES[0])
+}
+
+impl RulesMenu {
+    pub fn new(rules: &Rules) -> Self {
+        let mut menu = RulesMenu {
+            list: ListMenu::new(vec![]),
+        };
+        menu.refresh(rules);
+        menu
+    }
+
+    fn refresh(&mut self, rules: &Rules) {
+        let clock = match rules.shot_clock {
+            Some(seconds) => format!("{} s", seconds),
+            None => "off".to_string(),
+        };
+        let sudden_death = match rules.sudden_death {
+            Some(turns) => format!("after {} turns", turns),
+            None => "off".to_string(),
+        };
+        self.list.items = vec![
+            format!("Shot clock: {}", clock),
+            format!("Out of time: {}", rules.expiry.name()),
+            format!(
+                "World objects: {}",
+                if rules.objects { "on" } else { "off" }
+            ),
+            format!(
+                "Ricochet: {}",
+                if rules.ricochet {
+                    "every banana"
+                } else {
+                    "bouncy bananas"
+                }
+            ),
+            format!("Bounces: {}", rules.bounces),
+            format!("Gravity: {}", rules.gravity_name()),
+            format!("Lighter gravity (-{} m/s²)", GRAVITY_STEP),
+            format!("Heavier gravity (+{} m/s²)", GRAVITY_STEP),
+            format!(
+                "Wind: {}",
+                if rules.wind_field {
+                    "by terrain"
+                } else {
+                    "steady"
+                }
+            ),
+            format!(
+                "Hitting yourself: {}",
+                if rules.self_hit_penalty {
+                    "costs a point"
+                } else {
+                    "no penalty"
+                }
+            ),
+            format!(
+                "Friendly fire: {}",
+                if rules.friendly_fire { "on" } else { "off" }
+            ),
+            format!("Sudden death: {}", sudden_death),
+            format!("Flood: {}", rules.flood.name()),
+            "Back".to_string(),
+        ];
+    }
+
+    pub fn draw(&mut self, shared: &SharedAssets, window: &mut Window) -> Result<()> {
+        window.clear(Color::BLACK)?;
+        self.list.draw("Rules", shared, window)
+    }
+
+    // returns true when the screen is closed
+    pub fn event(&mut self, command: Command, rules: &mut Rules, sounds: &mut Vec<Sfx>) -> bool {
+        match self.list.event(command, sounds) {
+            Some(MenuEvent::Selected(0)) => rules.shot_clock = cycle_shot_clock(rules.shot_clock),
+            Some(MenuEvent::Selected(1)) => rules.expiry = rules.expiry.next(),
+            Some(MenuEvent::Selected(2)) => rules.objects = !rules.objects,
+            Some(MenuEvent::Selected(3)) => rules.ricochet = !rules.ricochet,
+            Some(MenuEvent::Selected(4)) => rules.bounces = cycle_bounces(rules.bounces),
+            Some(MenuEvent::Selected(5)) => rules.gravity = rules.next_gravity(),
+            Some(MenuEvent::Selected(6)) => rules.gravity = rules.step_gravity(-1.0),
+            Some(MenuEvent::Selected(7)) => rules.gravity = rules.step_gravity(1.0),
+            Some(MenuEvent::Selected(8)) => rules.wind_field = !rules.wind_field,
+            Some(MenuEvent::Selected(9)) => rules.self_hit_penalty = !rules.self_hit_penalty,
+            Some(MenuEvent::Selected(10)) => rules.friendly_fire = !rules.friendly_fire,
+            Some(MenuEvent::Selected(11)) => {
+                rules.sudden_death = cycle_sudden_death(rules.sudden_death)
+            }
+            Some(MenuEvent::Selected(12)) => rules.flood = rules.flood.next(),
+            Some(_) => return true,
+            None => return false,
+        }
+        self.refresh(rules);
+        false
+    }
+}
+
+impl PracticeMenu {
+    pub fn new(practice: &Practice) -> Self {
+        let mut menu = PracticeMenu {
+            list: ListMenu::new(vec![]),
+        };
+        menu.refresh(practice);
+        menu
+    }
+
+    fn refresh(&mut self, practice: &Practice) {
+        self.list.items = vec![
+            format!(
+                "Targets: {}",
+                if practice.moving { "moving" } else { "still" }
+            ),
+            format!("Trail: {}", if practice.trail { "shown" } else { "hidden" }),
+            "Start".to_string(),
+            "Back".to_string(),
+        ];
+    }
+
+    pub fn draw(&mut self, shared: &SharedAssets, window: &mut Window) -> Result<()> {
+        window.clear(Color::BLACK)?;
+        self.list.draw(PRACTICE, shared, window)
+    }
+
+    pub fn event(
+        &mut self,
+        command: Command,
+        practice: &mut Practice,
+        sounds: &mut Vec<Sfx>,
+    ) -> Option<PracticeEvent> {
+        match self.list.event(command, sounds)? {
+            MenuEvent::Selected(0) => practice.moving = !practice.moving,
+            MenuEvent::Selected(1) => practice.trail = !practice.trail,
+            MenuEvent::Selected(2) => return Some(PracticeEvent::Start),
+            _ => return Some(PracticeEvent::Back),
+        }
+        self.refresh(practice);
+        None
+    }
+}
+
+fn record_lines(record: &Record) -> Vec<String> {
+    let accuracy = match record.accuracy() {
+        Some(accuracy) => format!("{}%", percent(accuracy)),
+        None => "-".to_string(),
+    };
+    let shots_per_kill = match record.shots_per_kill() {
+        Some(shots) => format!("{:.1}", shots),
+        None => "-".to_string(),
+    };
+    vec![
+        format!(
+            "Matches: {} played, {} won",
+            record.matches_played, record.matches_won
+        ),
+        format!(
+            "Rounds: {} played, {} won",
+            record.rounds_played, record.rounds_won
+        ),
+        format!("Shots fired: {}", record.shots),
+        format!("Accuracy: {}", accuracy),
+        format!("Shots per kill: {}", shots_per_kill),
+        format!("Longest hit: {:.0} px", record.longest_hit),
+        format!("Self hits: {}", record.self_hits),
+        format!(
+            "Best streak: {} rounds, {} matches",
+            record.best_streak, record.best_match_streak
+        ),
+    ]
+}
+
+impl StatsMenu {
+    pub fn new(stats: &Stats) -> Self {
+        let mut menu = StatsMenu {
+            list: ListMenu::new(vec![]),
+            names: stats.players.keys().cloned().collect(),
+            shown: 0,
+        };
+        menu.refresh(stats);
+        menu
+    }
+
+    fn refresh(&mut self, stats: &Stats) {
+        let record = self
+            .names
+            .get(self.shown)
+            .and_then(|x| stats.players.get(x));
+        self.list.items = match (self.names.get(self.shown), record) {
+            (Some(name), Some(record)) => {
+                let mut items = vec![format!("< {} >", name)];
+                items.extend(record_lines(record));
+                items
+            }
+            _ => vec!["No matches played yet".to_string()],
+        };
+        self.list.items.push("Back".to_string());
+    }
+
+    pub fn draw(&mut self, shared: &SharedAssets, window: &mut Window) -> Result<()> {
+        window.clear(Color::BLACK)?;
+        self.list.draw("Statistics", shared, window)
+    }
+
+    // returns true when the screen is closed
+    pub fn event(&mut self, command: Command, stats: &Stats, sounds: &mut Vec<Sfx>) -> bool {
+        match self.list.event(command, sounds) {
+            Some(MenuEvent::Selected(0)) if !self.names.is_empty() => {
+                self.shown = (self.shown + 1) % self.names.len();
+                self.refresh(stats);
+                false
+            }
+            Some(MenuEvent::Selected(i)) => i + 1 == self.list.items.len(),
+            Some(MenuEvent::Back) => true,
+            None => false,
+        }
+    }
+}
+
+impl TournamentMenu {
+    pub fn new(tournament: &Tournament) -> Self {
+        let first = match tournament.next_match() {
+            Some(at) => {
+                let pairing = tournament.pairing(at);
+                let right = pairing.right.unwrap_or(pairing.left);
+                format!(
+                    "Play: {} vs {}",
+                    tournament.entrants[pairing.left].name, tournament.entrants[right].name
+                )
+            }
+            None => "New tournament".to_string(),
+        };
+        let mut lines = tournament.lines();
+        if let Some(champion) = tournament.champion() {
+            lines.insert(0, format!("{} wins the tournament!", champion.name));
+        }
+        let mut list = ListMenu::new(vec![
+            first,
+            "Export results".to_string(),
+            "Back".to_string(),
+        ]);
+        list.top = MENU_TOP + (TOURNAMENT_COLUMN_LINES + 1) as f32 * TOURNAMENT_LINE_HEIGHT;
+        TournamentMenu {
+            lines,
+            list,
+            playing: tournament.next_match().is_some(),
+        }
+    }
+
+    pub fn exported(&mut self, text: String) {
+        self.list.items[1] = text;
+    }
+
+    pub fn draw(&mut self, shared: &SharedAssets, window: &mut Window) -> Result<()> {
+        window.clear(Color::BLACK)?;
+        let lines = &self.lines;
+        shared.font.borrow_mut().execute(|f| {
+            // a second column once the first one is full
+            let columns = if lines.len() > TOURNAMENT_COLUMN_LINES {
+                2
+            } else {
+                1
+            };
+            for (i, line) in lines.iter().enumerate() {
+                let column = (i / TOURNAMENT_COLUMN_LINES).min(columns - 1);
+                let row = i - column * TOURNAMENT_COLUMN_LINES;
+                let x = WINDOW_X * (2 * column + 1) as f32 / (2 * columns) as f32;
+                let y = MENU_TOP + row as f32 * TOURNAMENT_LINE_HEIGHT;
+                if let Ok(ref text) = f.render(line, &shared.small_style) {
+                    window.draw_ex(
+                        &text.area().with_center((x, y)),
+                        Img(text),
+                        Transform::IDENTITY,
+                        7.0,
+                    );
+                }
+            }
+            Ok(())
+        })?;
+        self.list.draw("Tournament", shared, window)
+    }
+
+    pub fn event(&mut self, command: Command, sounds: &mut Vec<Sfx>) -> Option<TournamentEvent> {
+        match self.list.event(command, sounds)? {
+            MenuEvent::Selected(0) if self.playing => Some(TournamentEvent::Play),
+            MenuEvent::Selected(0) => Some(TournamentEvent::New),
+            MenuEvent::Selected(1) => Some(TournamentEvent::Export),
+            _ => Some(TournamentEvent::Back),
+        }
+    }
+}
+
+impl TournamentSetup {
+    pub fn new(candidates: Vec<Entrant>) -> Self {
+        let mut menu = TournamentSetup {
+            list: ListMenu::new(vec![]),
+            format: Format::SingleElimination,
+            chosen: vec![false; candidates.len()],
+            candidates,
+        };
+        menu.refresh();
+        menu
+    }
+
+    fn refresh(&mut self) {
+        let mut items = vec![format!("Format: {}", self.format.name())];
+        for (entrant, chosen) in self.candidates.iter().zip(self.chosen.iter()) {
+            let mark = if *chosen { "[x]" } else { "[ ]" };
+            items.push(format!("{} {}", mark, entrant.name));
+        }
+        items.push("Start".to_string());
+        items.push("Back".to_string());
+        self.list.items = items;
+    }
+
+    pub fn draw(&mut self, shared: &SharedAssets, window: &mut Window) -> Result<()> {
+        window.clear(Color::BLACK)?;
+        self.list.draw("New tournament", shared, window)
+    }
+
+    pub fn event(&mut self, command: Command, sounds: &mut Vec<Sfx>) -> Option<SetupEvent> {
+        let start = self.candidates.len() + 1;
+        match self.list.event(command, sounds)? {
+            MenuEvent::Selected(0) => self.format = self.format.next(),
+            MenuEvent::Selected(i) if i < start => self.chosen[i - 1] = !self.chosen[i - 1],
+            MenuEvent::Selected(i) if i == start => {
+                let entrants = self
+                    .candidates
+                    .iter()
+                    .zip(self.chosen.iter())
+                    .filter(|x| *x.1)
+                    .map(|x| x.0.clone())
+                    .collect();
+                match Tournament::new(self.format, entrants) {
+                    Ok(tournament) => return Some(SetupEvent::Start(tournament)),
+                    Err(e) => {
+                        self.list.items[start] = format!("Start: {}", e);
+                        return None;
+                    }
+                }
+            }
+            _ => return Some(SetupEvent::Back),
+        }
+        self.refresh();
+        None
+    }
+}
+
+fn describe(action: Action, bindings: &Bindings) -> String {
+    let inputs = bindings
+        .inputs(action)
+        .iter()
+        .map(|x| String::from(*x))
+        .collect::<Vec<_>>();
+    format!("{}: {}", action.name(), inputs.join(", "))
+}
+
+impl ControlsMenu {
+    pub fn new(bindings: &Bindings) -> Self {
+        let mut list = ListMenu::new(vec![]);
+        // every action gets a line, they only fit starting right below the title
+        list.top = CONTROLS_TOP;
+        let mut menu = ControlsMenu {
+            list,
+            listening: None,
+        };
+        menu.refresh(bindings);
+        menu
+    }
+
+    fn refresh(&mut self, bindings: &Bindings) {
+        let mut items = ACTIONS
+            .iter()
+            .map(|action| describe(*action, bindings))
+            .collect::<Vec<_>>();
+        items.push("Reset to defaults".to_string());
+        items.push("Back".to_string());
+        self.list.items = items;
+    }
+
+    pub fn draw(&mut self, shared: &SharedAssets, window: &mut Window) -> Result<()> {
+        window.clear(Color::BLACK)?;
+        self.list.draw("Controls", shared, window)
+    }
+
+    // returns true when the screen is closed
+    pub fn event(&mut self, event: &Event, bindings: &mut Bindings, sounds: &mut Vec<Sfx>) -> bool {
+        if let Some(action) = self.listening {
+            // escape always cancels, so a broken binding can not lock the player out
+            match Input::from_event(event) {
+                Some((Input::Key(Key::Escape), ButtonState::Pressed)) => (),
+                Some((input, ButtonState::Pressed)) => bindings.rebind(action, input),
+                _ => return false,
+            }
+            sounds.push(Sfx::MenuSelect);
+            self.listening = None;
+            self.refresh(bindings);
+            return false;
+        }
+
+        let command = match bindings.command(event, &MENU_ACTIONS) {
+            Some(command) => command,
+            None => return false,
+        };
+        match self.list.event(command, sounds) {
+            Some(MenuEvent::Selected(i)) if i < ACTIONS.len() => {
+                self.listening = Some(ACTIONS[i]);
+                self.list.items[i] = format!("{}: press a button", ACTIONS[i].name());
+                false
+            }
+            Some(MenuEvent::Selected(i)) if i == ACTIONS.len() => {
+                *bindings = Bindings::default();
+                self.refresh(bindings);
+                false
+            }
+            Some(_) => true,
+            None => false,
+        }
+    }
+}
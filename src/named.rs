@@ -0,0 +1,38 @@
+// Lists the player picks from by name, the profiles and the personalities.
+//
+// The lists come from the config file, so an entry is always looked up by its name and
+// never by where it was in the list.
+
+pub trait Named {
+    fn name(&self) -> &str;
+}
+
+pub fn find<'a, T: Named>(list: &'a [T], name: &str) -> Option<&'a T> {
+    list.iter().find(|x| x.name() == name)
+}
+
+// the one after the given one, by name so an edited list does not trip over it
+pub fn next<'a, T: Named>(list: &'a [T], current: &T) -> Option<&'a T> {
+    let i = list.iter().position(|x| x.name() == current.name());
+    let i = i.map_or(0, |i| (i + 1) % list.len());
+    list.get(i)
+}
+
+// the named entry, or by default the one at the given place in the list
+pub fn pick<T: Named + Clone + Default>(
+    list: &[T],
+    name: &Option<String>,
+    default: usize,
+    what: &str,
+) -> T {
+    if let Some(name) = name {
+        match find(list, name) {
+            Some(found) => return found.clone(),
+            None => eprintln!("Unknown {} {}, using the default one", what, name),
+        }
+    }
+    list.get(default)
+        .or_else(|| list.first())
+        .cloned()
+        .unwrap_or_default()
+}
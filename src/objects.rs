@@ -0,0 +1,161 @@
+// Things in the way of the banana besides the buildings.
+//
+// A window-washer platform rides up and down outside a wall, birds and a blimp cross the sky and
+// billboards stand on the roofs. Birds and billboards go down when they are hit or caught
+// in an explosion, the platform and the blimp only stop the banana.
+use crate::game_constants::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Vector},
+    graphics::Color,
+};
+use rand::Rng;
+use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Kind {
+    Platform,
+    Bird,
+    Blimp,
+    Billboard,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Object {
+    pub kind: Kind,
+    pub area: Rectangle,
+    // pixels per millisecond
+    pub velocity: Vector,
+    // the heights a platform turns around at
+    bounds: (f32, f32),
+}
+
+impl Object {
+    fn new(kind: Kind, area: Rectangle, velocity: Vector) -> Self {
+        Object {
+            kind,
+            area,
+            velocity,
+            bounds: (area.pos.y, area.pos.y),
+        }
+    }
+
+    pub fn destructible(&self) -> bool {
+        matches!(self.kind, Kind::Bird | Kind::Billboard)
+    }
+
+    pub fn color(&self) -> Color {
+        let hex = match self.kind {
+            Kind::Platform => PLATFORM_COLOR,
+            Kind::Bird => BIRD_COLOR,
+            Kind::Blimp => BLIMP_COLOR,
+            Kind::Billboard => BILLBOARD_COLOR,
+        };
+        Color::from_hex(hex)
+    }
+
+    pub fn update(&mut self) {
+        self.area.pos += self.velocity * DELTAT_MS;
+        match self.kind {
+            Kind::Platform => {
+                let (top, bottom) = self.bounds;
+                if self.area.pos.y < top || self.area.pos.y > bottom {
+                    self.area.pos.y = self.area.pos.y.clamp(top, bottom);
+                    self.velocity.y = -self.velocity.y;
+                }
+            }
+            // whatever flies out of one side of the sky comes back in on the other
+            Kind::Bird | Kind::Blimp => {
+                if self.area.pos.x > WINDOW_X {
+                    self.area.pos.x = -self.area.size.x;
+                } else if self.area.pos.x + self.area.size.x < 0.0 {
+                    self.area.pos.x = WINDOW_X;
+                }
+            }
+            Kind::Billboard => (),
+        }
+    }
+}
+
+fn flying(kind: Kind, size: (f32, f32), height: (f32, f32), speed: f32, rng: &mut Pcg32) -> Object {
+    let pos = Vector::new(
+        rng.gen_range(0.0, WINDOW_X - size.0),
+        rng.gen_range(height.0, height.1),
+    );
+    let speed = if rng.gen_bool(0.5) { speed } else { -speed };
+    Object::new(kind, Rectangle::new(pos, size), Vector::new(speed, 0.0))
+}
+
+// where a platform can hang outside a wall of the building and how far down it can ride,
+// half way down the facade at most and never into a lower neighbour or onto a gorilla
+fn hangs(b: &Rectangle, buildings: &[Rectangle], gorillas: &[Rectangle; 2]) -> Vec<(Vector, f32)> {
+    let size = Vector::from(PLATFORM_SIZE);
+    [b.pos.x - size.x, b.pos.x + b.size.x]
+        .iter()
+        .filter(|x| **x >= 0.0 && **x + size.x <= WINDOW_X)
+        .filter_map(|x| {
+            let below = |r: &&Rectangle| r.pos.x < x + size.x && r.pos.x + r.size.x > *x;
+            let floor = buildings
+                .iter()
+                .chain(gorillas.iter())
+                .filter(below)
+                .map(|r| r.pos.y)
+                .fold(WINDOW_Y, f32::min);
+            let bottom = (b.pos.y + b.size.y / 2.0).min(floor - size.y);
+            Some((Vector::new(*x, b.pos.y), bottom)).filter(|_| bottom > b.pos.y)
+        })
+        .collect()
+}
+
+// the objects of a new round, the buildings with a gorilla on top are left alone
+pub fn spawn(buildings: &[Rectangle], gorillas: [Rectangle; 2], rng: &mut Pcg32) -> Vec<Object> {
+    let free = buildings
+        .iter()
+        .filter(|b| {
+            gorillas
+                .iter()
+                .all(|g| g.pos.x + g.size.x < b.pos.x || g.pos.x > b.pos.x + b.size.x)
+        })
+        .collect::<Vec<_>>();
+    let mut objects = vec![];
+
+    let hangs = free
+        .iter()
+        .flat_map(|b| hangs(b, buildings, &gorillas))
+        .collect::<Vec<_>>();
+    if !hangs.is_empty() {
+        let (pos, bottom) = hangs[rng.gen_range(0, hangs.len())];
+        let mut platform = Object::new(
+            Kind::Platform,
+            Rectangle::new(pos, PLATFORM_SIZE),
+            Vector::new(0.0, PLATFORM_SPEED),
+        );
+        platform.bounds = (pos.y, bottom);
+        objects.push(platform);
+    }
+    for b in free.iter() {
+        if rng.gen_bool(BILLBOARD_CHANCE) {
+            let size = Vector::from(BILLBOARD_SIZE);
+            let pos = Vector::new(b.center().x - size.x / 2.0, b.pos.y - size.y);
+            objects.push(Object::new(
+                Kind::Billboard,
+                Rectangle::new(pos, size),
+                Vector::ZERO,
+            ));
+        }
+    }
+    for _ in 0..rng.gen_range(1, BIRDS_MAX + 1) {
+        objects.push(flying(Kind::Bird, BIRD_SIZE, BIRD_HEIGHT, BIRD_SPEED, rng));
+    }
+    if rng.gen_bool(BLIMP_CHANCE) {
+        objects.push(flying(
+            Kind::Blimp,
+            BLIMP_SIZE,
+            BLIMP_HEIGHT,
+            BLIMP_SPEED,
+            rng,
+        ));
+    }
+    objects
+}
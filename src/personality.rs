@@ -0,0 +1,89 @@
+use crate::game_constants::*;
+use crate::named::Named;
+
+use serde::{Deserialize, Serialize};
+
+// how a bot carries itself on screen, only power_variance changes where its shots go
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Personality {
+    pub name: String,
+    // how long the bot looks at the field before it starts to aim, in milliseconds
+    pub think_ms: (u32, u32),
+    // degrees per update the aim line turns towards the shot
+    pub aim_speed: f32,
+    // power added per update while charging
+    pub charge_speed: i32,
+    // the thrown power is off by up to this fraction of it
+    pub power_variance: f32,
+    // chance of saying something after each shot
+    pub chattiness: f32,
+    pub hit_taunts: Vec<String>,
+    pub miss_taunts: Vec<String>,
+}
+
+fn lines(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|x| x.to_string()).collect()
+}
+
+impl Default for Personality {
+    fn default() -> Self {
+        Personality {
+            name: "steady".to_string(),
+            think_ms: (600, 1200),
+            aim_speed: AIM_ROTATION_SPEED,
+            charge_speed: 3,
+            power_variance: 0.0,
+            chattiness: 0.3,
+            hit_taunts: lines(&["Right on target.", "As calculated.", "Down you go."]),
+            miss_taunts: lines(&["Adjusting.", "Noted.", "Almost."]),
+        }
+    }
+}
+
+// the personalities there are unless the config file lists its own, the first one is the default
+pub fn presets() -> Vec<Personality> {
+    vec![
+        Personality::default(),
+        Personality {
+            name: "hothead".to_string(),
+            think_ms: (100, 300),
+            aim_speed: 4.0,
+            charge_speed: 5,
+            power_variance: 0.08,
+            chattiness: 0.8,
+            hit_taunts: lines(&["BOOM!", "Eat that!", "Too easy!"]),
+            miss_taunts: lines(&["Argh!", "The wind cheated!", "Stand still!"]),
+        },
+        Personality {
+            name: "joker".to_string(),
+            think_ms: (900, 2000),
+            aim_speed: 1.0,
+            charge_speed: 2,
+            power_variance: 0.04,
+            chattiness: 1.0,
+            hit_taunts: lines(&["Banana delivery!", "Did that hurt?", "Peel the pain!"]),
+            miss_taunts: lines(&[
+                "Warning shot.",
+                "I meant to do that.",
+                "Just admiring the skyline.",
+            ]),
+        },
+        Personality {
+            name: "machine".to_string(),
+            think_ms: (0, 0),
+            aim_speed: 360.0,
+            charge_speed: 3,
+            power_variance: 0.0,
+            chattiness: 0.0,
+            hit_taunts: vec![],
+            miss_taunts: vec![],
+        },
+    ]
+}
+
+impl Named for Personality {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
@@ -0,0 +1,156 @@
+// The target range of practice mode.
+//
+// One gorilla throws at targets over the buildings of an ordinary round, nobody throws back.
+// Moving targets sway to and fro around where they were put. The range is cleared once every
+// target is down, the score is how many bananas that took and how many of them hit.
+use crate::game_constants::*;
+
+use quicksilver::geom::{Circle, Rectangle, Shape, Vector};
+use rand::Rng;
+use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize};
+
+// picked on the practice screen and kept in the config file
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Practice {
+    pub moving: bool,
+    // the paths of the last few bananas stay on the field
+    pub trail: bool,
+}
+
+impl Default for Practice {
+    fn default() -> Self {
+        Practice {
+            moving: false,
+            trail: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Target {
+    pub area: Circle,
+    // the target sways by up to this around the center, a still one does not
+    center: Vector,
+    sway: Vector,
+    phase: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Range {
+    pub settings: Practice,
+    pub targets: Vec<Target>,
+    pub shots: u32,
+    pub hits: u32,
+    // oldest first, the last one is the banana in flight
+    pub trails: Vec<Vec<Vector>>,
+}
+
+impl Target {
+    fn update(&mut self) {
+        self.phase += PRACTICE_SWAY_SPEED * DELTAT_MS;
+        self.area.pos = self.center + self.sway * self.phase.sin();
+    }
+}
+
+impl Range {
+    // the targets stand over the buildings beyond the thrower's reach of a short lob
+    pub fn new(
+        settings: Practice,
+        buildings: &[Rectangle],
+        thrower: &Rectangle,
+        rng: &mut Pcg32,
+    ) -> Self {
+        let near = thrower.pos.x + thrower.size.x + PRACTICE_DISTANCE_MIN;
+        let far = buildings
+            .iter()
+            .filter(|b| b.pos.x > near)
+            .collect::<Vec<_>>();
+        let mut targets = vec![];
+        for _ in 0..PRACTICE_TARGETS {
+            let b = match far.len() {
+                0 => &buildings[buildings.len() - 1],
+                n => far[rng.gen_range(0, n)],
+            };
+            let x = b.center().x + rng.gen_range(-0.25, 0.25) * b.size.x;
+            let y = b.pos.y - rng.gen_range(PRACTICE_HEIGHT.0, PRACTICE_HEIGHT.1);
+            let center = Vector::new(x, y.max(PRACTICE_TOP));
+            let sway = match (settings.moving, rng.gen_bool(0.5)) {
+                (false, _) => Vector::ZERO,
+                (true, true) => Vector::new(PRACTICE_SWAY, 0.0),
+                (true, false) => Vector::new(0.0, PRACTICE_SWAY),
+            };
+            let mut target = Target {
+                area: Circle::new(center, PRACTICE_TARGET_RADIUS),
+                center,
+                sway,
+                phase: rng.gen_range(0.0, std::f32::consts::TAU),
+            };
+            target.update();
+            targets.push(target);
+        }
+        Range {
+            settings,
+            targets,
+            shots: 0,
+            hits: 0,
+            trails: vec![],
+        }
+    }
+
+    pub fn update(&mut self) {
+        for target in self.targets.iter_mut() {
+            target.update();
+        }
+    }
+
+    pub fn hit(&self, circle: &Circle) -> Option<usize> {
+        self.targets.iter().position(|x| x.area.overlaps(circle))
+    }
+
+    pub fn take(&mut self, i: usize) {
+        self.targets.remove(i);
+        self.hits += 1;
+    }
+
+    pub fn throw(&mut self) {
+        self.shots += 1;
+        if self.settings.trail {
+            self.trails.push(vec![]);
+            if self.trails.len() > PRACTICE_TRAILS {
+                self.trails.remove(0);
+            }
+        }
+    }
+
+    // follows the banana in flight, a point every few pixels is enough to draw its path
+    pub fn trace(&mut self, pos: Vector) {
+        if let Some(trail) = self.trails.last_mut() {
+            if trail
+                .last()
+                .is_none_or(|x| x.distance(pos) >= PRACTICE_TRAIL_GAP)
+            {
+                trail.push(pos);
+            }
+        }
+    }
+
+    pub fn cleared(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    pub fn score(&self) -> String {
+        let accuracy = match self.shots {
+            0 => "-".to_string(),
+            shots => format!("{}%", (self.hits as f32 / shots as f32 * 100.0).round()),
+        };
+        format!(
+            "Targets: {}/{}   Shots: {}   Accuracy: {}",
+            self.hits,
+            self.hits as usize + self.targets.len(),
+            self.shots,
+            accuracy
+        )
+    }
+}
@@ -0,0 +1,72 @@
+use crate::game_constants::*;
+use crate::input::InputMode;
+use crate::named::Named;
+
+use quicksilver::graphics::Color;
+use serde::{Deserialize, Serialize};
+
+// a human player, the statistics are kept under the name
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    pub input: InputMode,
+    // multiplied into the gorilla sprite, as rrggbb
+    pub tint: String,
+    // one of the names in SKINS
+    pub skin: String,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile {
+            name: "Player".to_string(),
+            input: InputMode::Any,
+            tint: "ffffff".to_string(),
+            skin: SKINS[0].0.to_string(),
+        }
+    }
+}
+
+impl Profile {
+    // a tint that does not parse leaves the sprite as it is
+    pub fn color(&self) -> Color {
+        let hex = self.tint.trim_start_matches('#');
+        match u32::from_str_radix(hex, 16) {
+            Ok(rgb) if hex.len() == 6 => {
+                Color::from_rgba((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8, 1.0)
+            }
+            _ => Color::WHITE,
+        }
+    }
+
+    // the index into SKINS, an unknown skin gets the first one
+    pub fn skin_index(&self) -> usize {
+        SKINS
+            .iter()
+            .position(|x| x.0 == self.skin)
+            .unwrap_or_default()
+    }
+}
+
+// the profiles there are unless the config file lists its own
+pub fn presets() -> Vec<Profile> {
+    vec![
+        Profile {
+            name: "Player 1".to_string(),
+            ..Profile::default()
+        },
+        Profile {
+            name: "Player 2".to_string(),
+            tint: "ffc8a0".to_string(),
+            skin: SKINS[1].0.to_string(),
+            ..Profile::default()
+        },
+    ]
+}
+
+impl Named for Profile {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
@@ -0,0 +1,129 @@
+use crate::game_constants::*;
+
+use serde::{Deserialize, Serialize};
+
+// what happens to a turn whose shot clock runs out
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expiry {
+    // throws with the current aim and power, at least SHOT_CLOCK_POWER
+    Fire,
+    // the other side gets the turn
+    Forfeit,
+}
+
+// what rises from the bottom of the field in sudden death, both take the terrain and the
+// gorillas they reach alike
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Flood {
+    Water,
+    Lava,
+}
+
+// how a match is played, picked on the rules screen and kept in the config file
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    // seconds a human has for each throw, no limit without it
+    pub shot_clock: Option<u32>,
+    pub expiry: Expiry,
+    // platforms, birds, a blimp and billboards in the way, see objects.rs
+    pub objects: bool,
+    // every banana bounces off the walls it glances off, not only the bouncy ones
+    pub ricochet: bool,
+    // how many times a banana can bounce before the next wall stops it
+    pub bounces: u32,
+    // the wind changes with the altitude and behind the buildings, see wind.rs
+    pub wind_field: bool,
+    // in m/s², EARTH_GRAVITY plays like the game always did
+    pub gravity: f32,
+    // hitting yourself also costs one of your own points, not only gives the other side one
+    pub self_hit_penalty: bool,
+    // without it a banana flies through its thrower and the debris it brings down can not hurt it
+    pub friendly_fire: bool,
+    // turns into a round before the flood starts to rise, it never does without it
+    pub sudden_death: Option<u32>,
+    pub flood: Flood,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            shot_clock: None,
+            expiry: Expiry::Fire,
+            objects: false,
+            ricochet: false,
+            bounces: 3,
+            wind_field: false,
+            gravity: EARTH_GRAVITY,
+            self_hit_penalty: false,
+            friendly_fire: true,
+            sudden_death: None,
+            flood: Flood::Water,
+        }
+    }
+}
+
+impl Rules {
+    // how much faster the banana falls every update, a nonsense value counts as the earth's
+    pub fn gravity_push(&self) -> f32 {
+        match self.gravity {
+            g if g > 0.0 && g <= GRAVITY_MAX => GRAVITY * g / EARTH_GRAVITY,
+            _ => GRAVITY,
+        }
+    }
+
+    // the preset the gravity comes from, a custom one is shown by its value
+    pub fn gravity_name(&self) -> String {
+        match GRAVITY_PRESETS.iter().find(|x| x.1 == self.gravity) {
+            Some((name, g)) => format!("{} ({})", name, g),
+            None => format!("custom ({})", self.gravity),
+        }
+    }
+
+    // the next heavier preset, a custom gravity goes to the one above it
+    pub fn next_gravity(&self) -> f32 {
+        let next = GRAVITY_PRESETS.iter().find(|x| x.1 > self.gravity);
+        next.unwrap_or(&GRAVITY_PRESETS[0]).1
+    }
+
+    // a custom gravity one step lighter or heavier, kept to whole steps so the presets
+    // come up again on the way
+    pub fn step_gravity(&self, steps: f32) -> f32 {
+        let g = (self.gravity / GRAVITY_STEP).round() + steps;
+        (g / GRAVITY_STEP.recip()).clamp(GRAVITY_STEP, GRAVITY_MAX)
+    }
+}
+
+impl Expiry {
+    pub fn name(self) -> &'static str {
+        match self {
+            Expiry::Fire => "throw",
+            Expiry::Forfeit => "lose the turn",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Expiry::Fire => Expiry::Forfeit,
+            Expiry::Forfeit => Expiry::Fire,
+        }
+    }
+}
+
+impl Flood {
+    pub fn name(self) -> &'static str {
+        match self {
+            Flood::Water => "water",
+            Flood::Lava => "lava",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Flood::Water => Flood::Lava,
+            Flood::Lava => Flood::Water,
+        }
+    }
+}
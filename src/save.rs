@@ -0,0 +1,89 @@
+use crate::game::Game;
+
+use quicksilver::geom::Rectangle;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+
+pub const SAVE_PATH: &str = "gorillas-save.json";
+
+// bump whenever the layout of the saved game changes, older saves are then refused
+const SAVE_VERSION: u64 = 18;
+
+#[derive(Serialize)]
+struct SaveRef<'a> {
+    version: u64,
+    game: &'a Game,
+    parts: &'a [Vec<Rectangle>],
+}
+
+#[derive(Deserialize)]
+struct SaveFile {
+    game: Game,
+    parts: Vec<Vec<Rectangle>>,
+}
+
+pub enum LoadError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    Version(u64),
+    Corrupt,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "can not read the saved match: {}", e),
+            LoadError::Parse(e) => write!(f, "the saved match is damaged: {}", e),
+            LoadError::Version(version) => write!(
+                f,
+                "the saved match has version {}, this game only loads version {}",
+                version, SAVE_VERSION
+            ),
+            LoadError::Corrupt => write!(f, "the saved match does not fit the playing field"),
+        }
+    }
+}
+
+pub fn exists(path: &str) -> bool {
+    Path::new(path).is_file()
+}
+
+pub fn save(path: &str, game: &Game, parts: &[Vec<Rectangle>]) -> io::Result<()> {
+    let file = File::create(path)?;
+    let save = SaveRef {
+        version: SAVE_VERSION,
+        game,
+        parts: &parts[..game.building_count()],
+    };
+    serde_json::to_writer(BufWriter::new(file), &save)?;
+    Ok(())
+}
+
+// the terrain parts are copied back into the shared pools
+pub fn load(path: &str, pools: &mut [Vec<Rectangle>]) -> Result<Game, LoadError> {
+    let file = File::open(path).map_err(LoadError::Io)?;
+    let value: Value = serde_json::from_reader(BufReader::new(file)).map_err(LoadError::Parse)?;
+    // the version is checked first, an old layout would only give a confusing parse error
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version != SAVE_VERSION {
+        return Err(LoadError::Version(version));
+    }
+    let SaveFile { mut game, parts } = serde_json::from_value(value).map_err(LoadError::Parse)?;
+    if parts.len() != game.building_count() || parts.len() > pools.len() {
+        return Err(LoadError::Corrupt);
+    }
+
+    for pool in pools.iter_mut() {
+        pool.clear();
+    }
+    for (pool, saved) in pools.iter_mut().zip(parts) {
+        pool.extend(saved);
+    }
+    game.restored();
+    Ok(game)
+}
@@ -0,0 +1,192 @@
+use crate::game::{RoundSummary, Side};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+
+pub const STATS_PATH: &str = "gorillas-stats.json";
+
+// everything one player has done across all sessions
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Record {
+    pub matches_played: u32,
+    pub matches_won: u32,
+    pub rounds_played: u32,
+    pub rounds_won: u32,
+    pub shots: u32,
+    // rounds won with the player's own banana
+    pub hits: u32,
+    // from the thrower to where the banana hit, in pixels
+    pub longest_hit: f32,
+    // rounds lost to the player's own banana
+    pub self_hits: u32,
+    // rounds and matches won in a row
+    pub streak: u32,
+    pub best_streak: u32,
+    pub match_streak: u32,
+    pub best_match_streak: u32,
+}
+
+// the records by player name, kept sorted so the file and the screen list them alike
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub players: BTreeMap<String, Record>,
+}
+
+impl Record {
+    pub fn accuracy(&self) -> Option<f32> {
+        match self.shots {
+            0 => None,
+            shots => Some(self.hits as f32 / shots as f32),
+        }
+    }
+
+    pub fn shots_per_kill(&self) -> Option<f32> {
+        match self.hits {
+            0 => None,
+            hits => Some(self.shots as f32 / hits as f32),
+        }
+    }
+
+    fn round(&mut self, won: bool, shots: u32) {
+        self.rounds_played += 1;
+        self.shots += shots;
+        if won {
+            self.rounds_won += 1;
+            self.streak += 1;
+            self.best_streak = self.best_streak.max(self.streak);
+        } else {
+            self.streak = 0;
+        }
+    }
+
+    fn hit(&mut self, distance: f32) {
+        self.hits += 1;
+        self.longest_hit = self.longest_hit.max(distance);
+    }
+
+    fn finish(&mut self, won: Option<bool>) {
+        self.matches_played += 1;
+        match won {
+            Some(true) => {
+                self.matches_won += 1;
+                self.match_streak += 1;
+                self.best_match_streak = self.best_match_streak.max(self.match_streak);
+            }
+            Some(false) => self.match_streak = 0,
+            // an abandoned match neither adds to the streak nor breaks it
+            None => (),
+        }
+    }
+}
+
+impl Stats {
+    // a missing or broken stats file is not fatal, the counting starts over
+    pub fn load(path: &str) -> Self {
+        match File::open(path) {
+            Ok(file) => serde_json::from_reader(BufReader::new(file)).unwrap_or_else(|e| {
+                eprintln!("Failed to parse {}: {}", path, e);
+                Stats::default()
+            }),
+            Err(_) => Stats::default(),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let file = File::create(path)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+        Ok(())
+    }
+
+    fn player(&mut self, name: &str) -> &mut Record {
+        self.players.entry(name.to_string()).or_default()
+    }
+
+    // a player against itself, a mirror match of one bot or two sides on one profile, would
+    // win and lose at once, it is left out of the record
+    pub fn add_round(&mut self, left: &str, right: &str, round: &RoundSummary) {
+        if left == right {
+            return;
+        }
+        self.player(left)
+            .round(round.winner == Side::Left, round.shots_left);
+        self.player(right)
+            .round(round.winner == Side::Right, round.shots_right);
+        // a flood ends the round without anybody's banana
+        if round.flooded {
+            return;
+        }
+        // a banana that came back down on its own thrower gives the other side the round
+        if round.thrower == round.winner {
+            let name = match round.winner {
+                Side::Left => left,
+                Side::Right => right,
+            };
+            self.player(name).hit(round.distance);
+        } else {
+            let name = match round.thrower {
+                Side::Left => left,
+                Side::Right => right,
+            };
+            self.player(name).self_hits += 1;
+        }
+    }
+
+    // the winner is None for a match that was left undecided
+    pub fn add_match(&mut self, left: &str, right: &str, winner: Option<Side>) {
+        if left == right {
+            return;
+        }
+        self.player(left).finish(winner.map(|x| x == Side::Left));
+        self.player(right).finish(winner.map(|x| x == Side::Right));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round(winner: Side) -> RoundSummary {
+        RoundSummary {
+            winner,
+            thrower: winner,
+            distance: 300.0,
+            shots_left: 2,
+            shots_right: 3,
+            frames: 600,
+            flooded: false,
+        }
+    }
+
+    #[test]
+    fn sides_keep_their_own_records() {
+        let mut stats = Stats::default();
+        stats.add_round("a", "b", &round(Side::Left));
+        stats.add_match("a", "b", Some(Side::Left));
+        let (a, b) = (&stats.players["a"], &stats.players["b"]);
+        assert_eq!(
+            (a.rounds_played, a.rounds_won, a.shots, a.hits),
+            (1, 1, 2, 1)
+        );
+        assert_eq!(
+            (b.rounds_played, b.rounds_won, b.shots, b.hits),
+            (1, 0, 3, 0)
+        );
+        assert_eq!((a.matches_won, a.match_streak, b.matches_won), (1, 1, 0));
+    }
+
+    #[test]
+    fn same_name_on_both_sides_is_not_recorded() {
+        let mut stats = Stats::default();
+        stats.add_round("a", "b", &round(Side::Left));
+        stats.add_round("a", "a", &round(Side::Right));
+        stats.add_match("a", "a", Some(Side::Right));
+        let a = &stats.players["a"];
+        assert_eq!((a.rounds_played, a.shots, a.streak), (1, 2, 1));
+        assert_eq!(a.matches_played, 0);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::bracket::Bracket;
+use crate::game::{Difficulty, Side};
+use crate::game_constants::*;
+use crate::wind;
+
+use quicksilver::geom::{Rectangle, Shape, Vector};
+use serde::{Deserialize, Serialize};
+
+use std::convert::TryFrom;
+
+// what a bot gets to see of the world when it is its turn
+pub struct World<'a> {
+    pub side: Side,
+    pub me: Rectangle,
+    pub target: Rectangle,
+    // the intact parts of every building
+    pub buildings: &'a [Vec<Rectangle>],
+    // added to the speed of the banana every tick, like gravity, see wind_at
+    pub wind: Vector,
+    // the wind changes over the field, sheltered by the whole buildings
+    pub wind_field: bool,
+    pub shelter: &'a [Rectangle],
+    pub gravity: f32,
+    // with air drag the wind is the speed of the air, see Ballistics::step
+    pub drag: f32,
+    // where the previous shots of this side ended this round, oldest first
+    pub landings: &'a [Vector],
+}
+
+impl World<'_> {
+    // how much of the wind pushes the banana at pos, all of it without the wind field
+    pub fn wind_scale(&self, pos: Vector) -> f32 {
+        match self.wind_field {
+            true => wind::scale(pos, self.wind, self.shelter),
+            false => 1.0,
+        }
+    }
+
+    pub fn wind_at(&self, pos: Vector) -> Vector {
+        self.wind * self.wind_scale(pos)
+    }
+}
+
+// the banana leaves with a speed of dir * power * SHOT_SPEED_RATIO, power is capped at POWER_MAX
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Shot {
+    pub dir: Vector,
+    pub power: i32,
+}
+
+pub trait Strategy {
+    // asked every tick of the bot's turn until it returns a shot, so it can take its time
+    fn shoot(&mut self, world: &World) -> Option<Shot>;
+
+    // the terrain and the gorillas have moved, the landings start over
+    fn new_round(&mut self) {}
+}
+
+pub struct Registration {
+    pub name: &'static str,
+    pub create: fn(Difficulty) -> Box<dyn Strategy>,
+}
+
+// new strategies are added here to show up in the menu and on the command line,
+// the first one is the default
+pub const STRATEGIES: &[Registration] = &[
+    Registration {
+        name: "classic",
+        create: Classic::create,
+    },
+    Registration {
+        name: "bracket",
+        create: Bracket::create,
+    },
+];
+
+// an entry of STRATEGIES, saved by name
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct StrategyId(usize);
+
+impl StrategyId {
+    pub fn find(name: &str) -> Option<Self> {
+        STRATEGIES
+            .iter()
+            .position(|x| x.name == name)
+            .map(StrategyId)
+    }
+
+    pub fn name(self) -> &'static str {
+        STRATEGIES[self.0].name
+    }
+
+    pub fn next(self) -> Self {
+        StrategyId((self.0 + 1) % STRATEGIES.len())
+    }
+
+    pub fn create(self, difficulty: Difficulty) -> Box<dyn Strategy> {
+        (STRATEGIES[self.0].create)(difficulty)
+    }
+}
+
+impl From<StrategyId> for String {
+    fn from(id: StrategyId) -> String {
+        id.name().to_string()
+    }
+}
+
+impl TryFrom<String> for StrategyId {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        StrategyId::find(&s).ok_or_else(|| format!("unknown strategy: {}", s))
+    }
+}
+
+// always throws at full power and walks the angle up or down after every miss
+pub struct Classic {
+    difficulty: Difficulty,
+}
+
+impl Classic {
+    pub fn new(difficulty: Difficulty) -> Self {
+        Classic { difficulty }
+    }
+
+    fn create(difficulty: Difficulty) -> Box<dyn Strategy> {
+        Box::new(Classic::new(difficulty))
+    }
+
+    fn correct(&self, dir: Vector, pos: Vector, target: Vector, landing: Vector) -> Vector {
+        let mut inc = if landing.x < target.x {
+            BOT_AIM_INC_Y
+        } else {
+            -BOT_AIM_INC_Y
+        };
+        if pos.x < target.x {
+            inc = -inc;
+        }
+        if let Difficulty::Hard = self.difficulty {
+            // smaller corrections the closer the last shot landed
+            let miss = (landing.x - target.x).abs() / BOT_HARD_MISS_DISTANCE;
+            inc *= miss.clamp(0.25, 1.0);
+        }
+        Vector::new(dir.x, dir.y + inc).normalize()
+    }
+}
+
+impl Strategy for Classic {
+    fn shoot(&mut self, world: &World) -> Option<Shot> {
+        let pos = world.me.center();
+        let target = world.target.center();
+        let mut dir = (target - pos).normalize();
+        for landing in world.landings.iter() {
+            dir = self.correct(dir, pos, target, *landing);
+        }
+        Some(Shot {
+            dir,
+            power: BOT_COUNTER_MAX,
+        })
+    }
+}
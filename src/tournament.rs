@@ -0,0 +1,463 @@
+// A hotseat tournament: every match is an ordinary game between two entrants.
+//
+// Single elimination pairs the entrants up and adds the next round once every match of the
+// current one is decided, an odd number of winners gives the middle one a bye. Round robin
+// plans every round up front so each entrant meets every other one once.
+use crate::game::{bot_name, Difficulty, Player, Side};
+use crate::game_constants::*;
+use crate::profile::Profile;
+use crate::strategy::StrategyId;
+
+use serde::Serialize;
+
+use std::cmp::Reverse;
+use std::fs::File;
+use std::io::{self, BufWriter};
+
+pub const TOURNAMENT_PATH: &str = "gorillas-tournament.json";
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    SingleElimination,
+    RoundRobin,
+}
+
+#[derive(Clone, Debug)]
+pub struct Entrant {
+    pub name: String,
+    pub player: Player,
+    // the profile a human plays under, bots leave it at the default
+    pub profile: Profile,
+}
+
+#[derive(Clone, Debug)]
+pub struct Pairing {
+    pub left: usize,
+    // none is a bye, the left entrant goes through without playing
+    pub right: Option<usize>,
+    pub points: (u32, u32),
+    pub winner: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Standing {
+    pub name: String,
+    pub played: u32,
+    pub won: u32,
+    pub lost: u32,
+    pub rounds_won: u32,
+    pub rounds_lost: u32,
+}
+
+pub struct Tournament {
+    pub format: Format,
+    pub entrants: Vec<Entrant>,
+    pub rounds: Vec<Vec<Pairing>>,
+}
+
+// what ends up in the exported file
+#[derive(Serialize)]
+struct MatchReport<'a> {
+    round: usize,
+    left: &'a str,
+    right: Option<&'a str>,
+    points_left: u32,
+    points_right: u32,
+    winner: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    format: Format,
+    entrants: Vec<&'a str>,
+    matches: Vec<MatchReport<'a>>,
+    standings: Vec<Standing>,
+    champion: Option<&'a str>,
+}
+
+impl Format {
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::SingleElimination => "single elimination",
+            Format::RoundRobin => "round robin",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Format::SingleElimination => Format::RoundRobin,
+            Format::RoundRobin => Format::SingleElimination,
+        }
+    }
+}
+
+impl Entrant {
+    pub fn human(profile: &Profile) -> Self {
+        Entrant {
+            name: profile.name.clone(),
+            player: Player::Human,
+            profile: profile.clone(),
+        }
+    }
+
+    pub fn bot(strategy: StrategyId, difficulty: Difficulty) -> Self {
+        Entrant {
+            name: bot_name(strategy.name(), difficulty),
+            player: Player::Bot(strategy, difficulty),
+            profile: Profile::default(),
+        }
+    }
+}
+
+impl Pairing {
+    fn new(left: usize, right: Option<usize>) -> Self {
+        Pairing {
+            left,
+            right,
+            points: (0, 0),
+            winner: if right.is_none() { Some(left) } else { None },
+        }
+    }
+}
+
+// the first entrant meets the last, the second the one before the last and so on
+fn pair_up(entrants: &[usize]) -> Vec<Pairing> {
+    let half = entrants.len().div_ceil(2);
+    (0..half)
+        .map(|i| {
+            let right = entrants.len() - 1 - i;
+            Pairing::new(entrants[i], Some(entrants[right]).filter(|_| right != i))
+        })
+        .collect()
+}
+
+// the circle method, one entrant stays put while the others turn around it
+fn round_robin(count: usize) -> Vec<Vec<Pairing>> {
+    let mut circle = (0..count).map(Some).collect::<Vec<_>>();
+    if count % 2 == 1 {
+        circle.push(None);
+    }
+    let len = circle.len();
+    let mut rounds = vec![];
+    for _ in 0..len - 1 {
+        let round = (0..len / 2)
+            .filter_map(|i| match (circle[i], circle[len - 1 - i]) {
+                (Some(left), Some(right)) => Some(Pairing::new(left, Some(right))),
+                _ => None,
+            })
+            .collect();
+        rounds.push(round);
+        circle[1..].rotate_right(1);
+    }
+    rounds
+}
+
+impl Tournament {
+    pub fn new(format: Format, mut entrants: Vec<Entrant>) -> Result<Self, String> {
+        if entrants.len() < TOURNAMENT_MIN_ENTRANTS || entrants.len() > TOURNAMENT_MAX_ENTRANTS {
+            return Err(format!(
+                "a tournament needs {} to {} players, not {}",
+                TOURNAMENT_MIN_ENTRANTS,
+                TOURNAMENT_MAX_ENTRANTS,
+                entrants.len()
+            ));
+        }
+        // the same bot can enter twice, the results need to tell them apart
+        let names = entrants.iter().map(|x| x.name.clone()).collect::<Vec<_>>();
+        for (i, entrant) in entrants.iter_mut().enumerate() {
+            let same = names[..i].iter().filter(|x| **x == names[i]).count();
+            if same > 0 {
+                entrant.name = format!("{} {}", names[i], same + 1);
+            }
+        }
+        let rounds = match format {
+            Format::SingleElimination => {
+                vec![pair_up(&(0..entrants.len()).collect::<Vec<_>>())]
+            }
+            Format::RoundRobin => round_robin(entrants.len()),
+        };
+        Ok(Tournament {
+            format,
+            entrants,
+            rounds,
+        })
+    }
+
+    // the round and the place in it of the first match still to play
+    pub fn next_match(&self) -> Option<(usize, usize)> {
+        self.rounds
+            .iter()
+            .enumerate()
+            .find_map(|(round, pairings)| {
+                pairings
+                    .iter()
+                    .position(|x| x.winner.is_none())
+                    .map(|i| (round, i))
+            })
+    }
+
+    pub fn pairing(&self, at: (usize, usize)) -> &Pairing {
+        &self.rounds[at.0][at.1]
+    }
+
+    pub fn record(&mut self, at: (usize, usize), points: (u32, u32), winner: Side) {
+        let pairing = &mut self.rounds[at.0][at.1];
+        pairing.points = points;
+        pairing.winner = match (winner, pairing.right) {
+            (Side::Right, Some(right)) => Some(right),
+            _ => Some(pairing.left),
+        };
+
+        let last = self.rounds.last().map(Vec::as_slice).unwrap_or_default();
+        let winners = last.iter().filter_map(|x| x.winner).collect::<Vec<_>>();
+        if self.format == Format::SingleElimination
+            && winners.len() == last.len()
+            && winners.len() > 1
+        {
+            self.rounds.push(pair_up(&winners));
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.next_match().is_none()
+    }
+
+    pub fn champion(&self) -> Option<&Entrant> {
+        if !self.is_over() {
+            return None;
+        }
+        let index = match self.format {
+            Format::SingleElimination => self.rounds.last()?.first()?.winner?,
+            Format::RoundRobin => self.ranking().first()?.0,
+        };
+        self.entrants.get(index)
+    }
+
+    // entrants with their record, the best first
+    fn ranking(&self) -> Vec<(usize, Standing)> {
+        let mut table = self
+            .entrants
+            .iter()
+            .map(|x| Standing {
+                name: x.name.clone(),
+                ..Standing::default()
+            })
+            .collect::<Vec<_>>();
+        for pairing in self.rounds.iter().flatten() {
+            let (right, winner) = match (pairing.right, pairing.winner) {
+                (Some(right), Some(winner)) => (right, winner),
+                _ => continue,
+            };
+            let sides = [
+                (pairing.left, pairing.points.0, pairing.points.1),
+                (right, pairing.points.1, pairing.points.0),
+            ];
+            for (i, scored, conceded) in sides {
+                let standing = &mut table[i];
+                standing.played += 1;
+                standing.rounds_won += scored;
+                standing.rounds_lost += conceded;
+                if winner == i {
+                    standing.won += 1;
+                } else {
+                    standing.lost += 1;
+                }
+            }
+        }
+        let mut ranking = table.into_iter().enumerate().collect::<Vec<_>>();
+        ranking.sort_by_key(|(_, x)| {
+            (
+                Reverse(x.won),
+                Reverse(x.rounds_won as i64 - x.rounds_lost as i64),
+                x.name.clone(),
+            )
+        });
+        ranking
+    }
+
+    pub fn standings(&self) -> Vec<Standing> {
+        self.ranking().into_iter().map(|x| x.1).collect()
+    }
+
+    fn describe(&self, pairing: &Pairing) -> String {
+        let left = &self.entrants[pairing.left].name;
+        match (pairing.right, pairing.winner) {
+            (None, _) => format!("{} (bye)", left),
+            (Some(right), None) => format!("{} vs {}", left, self.entrants[right].name),
+            (Some(right), Some(_)) => format!(
+                "{} {}-{} {}",
+                left, pairing.points.0, pairing.points.1, self.entrants[right].name
+            ),
+        }
+    }
+
+    // what the screen between the matches shows
+    pub fn lines(&self) -> Vec<String> {
+        match self.format {
+            Format::SingleElimination => {
+                let round = self.next_match().map_or(self.rounds.len() - 1, |x| x.0);
+                let mut lines = vec![format!("Round {}", round + 1)];
+                lines.extend(self.rounds[round].iter().map(|x| self.describe(x)));
+                lines
+            }
+            Format::RoundRobin => self
+                .standings()
+                .iter()
+                .enumerate()
+                .map(|(i, x)| format!("{}. {}  {}-{}", i + 1, x.name, x.won, x.lost))
+                .collect(),
+        }
+    }
+
+    pub fn export(&self, path: &str) -> io::Result<()> {
+        let name = |i: usize| self.entrants[i].name.as_str();
+        let matches = self
+            .rounds
+            .iter()
+            .enumerate()
+            .flat_map(|(round, pairings)| pairings.iter().map(move |x| (round, x)))
+            .map(|(round, x)| MatchReport {
+                round: round + 1,
+                left: name(x.left),
+                right: x.right.map(name),
+                points_left: x.points.0,
+                points_right: x.points.1,
+                winner: x.winner.map(name),
+            })
+            .collect();
+        let report = Report {
+            format: self.format,
+            entrants: self.entrants.iter().map(|x| x.name.as_str()).collect(),
+            matches,
+            standings: self.standings(),
+            champion: self.champion().map(|x| x.name.as_str()),
+        };
+        let file = File::create(path)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), &report)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUNTS: [usize; 5] = [4, 5, 7, 8, 16];
+
+    fn tournament(format: Format, count: usize) -> Tournament {
+        let entrants = (0..count)
+            .map(|_| Entrant::bot(StrategyId::default(), Difficulty::Normal))
+            .collect();
+        Tournament::new(format, entrants).unwrap()
+    }
+
+    // every entrant at most once in a round
+    fn check_round(round: &[Pairing], count: usize) {
+        let mut seen = vec![false; count];
+        for i in round
+            .iter()
+            .flat_map(|x| std::iter::once(x.left).chain(x.right))
+        {
+            assert!(!seen[i], "entrant {} plays twice in a round", i);
+            seen[i] = true;
+        }
+    }
+
+    #[test]
+    fn new_turns_down_too_few_or_too_many() {
+        for count in [TOURNAMENT_MIN_ENTRANTS - 1, TOURNAMENT_MAX_ENTRANTS + 1] {
+            let entrants = (0..count)
+                .map(|_| Entrant::bot(StrategyId::default(), Difficulty::Normal))
+                .collect();
+            assert!(Tournament::new(Format::SingleElimination, entrants).is_err());
+        }
+    }
+
+    #[test]
+    fn the_first_round_gives_an_odd_one_a_bye() {
+        for count in COUNTS {
+            let tournament = tournament(Format::SingleElimination, count);
+            let round = &tournament.rounds[0];
+            check_round(round, count);
+            let byes = round
+                .iter()
+                .filter(|x| x.right.is_none())
+                .collect::<Vec<_>>();
+            assert_eq!(byes.len(), count % 2, "{} entrants", count);
+            for bye in byes {
+                assert_eq!(bye.left, count / 2);
+                assert_eq!(bye.winner, Some(bye.left));
+            }
+            let seated = round
+                .iter()
+                .map(|x| 1 + x.right.map_or(0, |_| 1))
+                .sum::<usize>();
+            assert_eq!(seated, count);
+        }
+    }
+
+    #[test]
+    fn round_robin_meets_everyone_once() {
+        for count in COUNTS {
+            let rounds = tournament(Format::RoundRobin, count).rounds;
+            assert_eq!(rounds.len(), count - 1 + count % 2, "{} entrants", count);
+            let mut met = vec![];
+            for round in rounds.iter() {
+                check_round(round, count);
+                for pairing in round {
+                    let right = pairing.right.expect("round robin has no byes");
+                    met.push((pairing.left.min(right), pairing.left.max(right)));
+                }
+            }
+            met.sort_unstable();
+            let all = (0..count)
+                .flat_map(|a| (a + 1..count).map(move |b| (a, b)))
+                .collect::<Vec<_>>();
+            assert_eq!(met, all, "{} entrants", count);
+        }
+    }
+
+    #[test]
+    fn elimination_adds_a_round_once_the_last_is_decided() {
+        for count in COUNTS {
+            let mut tournament = tournament(Format::SingleElimination, count);
+            let mut played = 0;
+            while let Some(at) = tournament.next_match() {
+                assert_eq!(at.0, tournament.rounds.len() - 1);
+                tournament.record(at, (2, 1), Side::Left);
+                played += 1;
+                let round = &tournament.rounds[at.0];
+                let decided = round.iter().all(|x| x.winner.is_some());
+                let expected = match decided && round.len() > 1 {
+                    true => at.0 + 2,
+                    false => at.0 + 1,
+                };
+                assert_eq!(tournament.rounds.len(), expected, "{} entrants", count);
+                if let Some(next) = tournament.rounds.get(at.0 + 1) {
+                    check_round(next, count);
+                }
+            }
+            // every match knocks one entrant out
+            assert_eq!(played, count - 1);
+            assert_eq!(tournament.rounds.last().map(Vec::len), Some(1));
+            assert!(tournament.champion().is_some());
+        }
+    }
+
+    #[test]
+    fn round_robin_plays_the_planned_rounds() {
+        for count in COUNTS {
+            let mut tournament = tournament(Format::RoundRobin, count);
+            let planned = tournament.rounds.len();
+            while let Some(at) = tournament.next_match() {
+                tournament.record(at, (1, 2), Side::Right);
+            }
+            assert_eq!(tournament.rounds.len(), planned);
+            for standing in tournament.standings() {
+                assert_eq!(standing.played as usize, count - 1, "{} entrants", count);
+            }
+            assert!(tournament.champion().is_some());
+        }
+    }
+}
@@ -0,0 +1,46 @@
+// The wind of a round as it blows at one spot of the field.
+//
+// The round draws one wind for the whole sky, this scales it by the altitude layer the spot
+// is in, stronger higher up, and by the shelter of the buildings upwind of it. Below the roof
+// of a building the wind picks up again over WIND_SHADOW_LENGTH times the height under it.
+use crate::game_constants::*;
+
+use quicksilver::geom::{Rectangle, Vector};
+
+// how much of the round's wind blows at the height of pos
+fn altitude(pos: Vector) -> f32 {
+    WIND_LAYERS
+        .iter()
+        .find(|x| pos.y < x.0)
+        .map_or(WIND_LAYERS[WIND_LAYERS.len() - 1].1, |x| x.1)
+}
+
+// how much of the wind gets past the buildings, one for open sky
+fn shelter(pos: Vector, wind: Vector, buildings: &[Rectangle]) -> f32 {
+    buildings
+        .iter()
+        .filter(|b| pos.y > b.pos.y)
+        .filter_map(|b| {
+            // from the downwind wall of the building to pos, along the wind
+            let behind = if wind.x > 0.0 {
+                pos.x - b.pos.x - b.size.x
+            } else {
+                b.pos.x - pos.x
+            };
+            let length = (pos.y - b.pos.y) * WIND_SHADOW_LENGTH;
+            if behind < 0.0 || behind > length {
+                return None;
+            }
+            Some(WIND_SHADOW_MIN + (1.0 - WIND_SHADOW_MIN) * behind / length)
+        })
+        .fold(1.0, f32::min)
+}
+
+// how much of the round's wind blows at pos, the direction of the wind decides the shelter
+pub fn scale(pos: Vector, wind: Vector, buildings: &[Rectangle]) -> f32 {
+    altitude(pos) * shelter(pos, wind, buildings)
+}
+
+pub fn at(pos: Vector, wind: Vector, buildings: &[Rectangle]) -> Vector {
+    wind * scale(pos, wind, buildings)
+}